@@ -1,26 +1,171 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
+
+/// Rules for turning C# names into C++ identifiers and file paths
+pub trait NameMangler {
+    fn namespace_cpp(&self, string: &str) -> String {
+        if string.is_empty() {
+            "GlobalNamespace".to_owned()
+        } else {
+            string.replace(['<', '>', '`', '/'], "_").replace('.', "::")
+        }
+    }
+    fn name_cpp(&self, string: &str) -> String {
+        // Coincidentally the same as path_name
+        string.replace(['<', '`', '>', '/', '.'], "_")
+    }
+    fn namespace_path(&self, string: &str) -> String {
+        string.replace(['<', '>', '`', '/'], "_").replace('.', "/")
+    }
+    fn path_name(&self, string: &str) -> String {
+        string.replace(['<', '>', '`', '.', '/'], "_")
+    }
+}
+
+/// The built in mangling rules
+pub struct DefaultNameMangler;
+
+impl NameMangler for DefaultNameMangler {}
+
+/// Whether namespace is parent or one of its sub namespaces, System contains System.IO
+/// but not SystemFoo
+fn is_in_namespace(namespace: &str, parent: &str) -> bool {
+    namespace == parent
+        || namespace
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
 
 pub struct GenerationConfig {
     pub source_path: PathBuf,
     pub header_path: PathBuf,
+    /// Extension of generated headers, without the leading dot
+    pub header_extension: String,
+    /// Prefix file names with their namespace instead of nesting directories
+    pub flat_namespace_paths: bool,
+    /// Emit a static klass() accessor on every type
+    pub klass_accessor: bool,
+    /// Emit a checked try_cast on reference types
+    pub casts: bool,
+    /// Emit relational operators for types implementing IComparable or IComparable<T>
+    pub comparisons: bool,
+    /// Declare every instance method const
+    pub const_methods: bool,
+    /// Call methods with known addresses through a function pointer instead of the runtime
+    pub direct_calls: bool,
+    /// Additional includes for types, keyed by C# full name
+    pub extra_includes: HashMap<String, Vec<PathBuf>>,
+    /// Field offsets to use instead of the metadata's, keyed by C# full name then field name
+    pub offset_overrides: HashMap<String, HashMap<String, u32>>,
+    /// Emit a constexpr name/offset table of instance fields per type
+    pub field_tables: bool,
+    /// Emit a constexpr offset constant per instance field
+    pub field_offsets: bool,
+    /// Emit a constexpr name/vtable slot table of methods per type
+    pub slot_tables: bool,
+    /// Namespaces, and their children, that are not filled
+    pub exclude_namespaces: Vec<String>,
+    /// Only generate fields and nested types, no methods, properties or constructors
+    pub layout_only: bool,
+    /// Write headers under a directory per owning assembly
+    pub group_by_assembly: bool,
+    /// Specialize cordl::is_convertible for every base type and interface
+    pub convertible_traits: bool,
+    /// Skip compiler generated members such as backing fields and lambdas
+    pub hide_compiler_generated: bool,
+    /// Emit a hash of each type's layout and method signatures
+    pub abi_hash: bool,
+    /// Skip property wrapper members, leaving only the get_/set_ methods
+    pub no_property_wrappers: bool,
+    /// Skip the MetadataGetter size structs, methods look up their MethodInfo by name
+    pub no_size_structs: bool,
+    /// Namespace generated bodies call il2cpp_utils helpers through
+    pub il2cpp_utils_namespace: String,
+    /// Namespace generated bodies call il2cpp_functions, the runtime's exports, through
+    pub il2cpp_functions_namespace: String,
+    /// Write each context's forward declares to a separate header included once
+    pub forward_declare_headers: bool,
+    /// Include generated headers as <path> relative to header_path instead of quoted
+    pub angle_includes: bool,
+    /// Name mangling rules, DefaultNameMangler unless overridden
+    pub name_mangler: Box<dyn NameMangler>,
 }
 
 impl GenerationConfig {
     pub fn namespace_cpp(&self, string: &str) -> String {
-        if string.is_empty() {
-            "GlobalNamespace".to_owned()
-        } else {
-            string.replace(['<', '>', '`', '/'], "_").replace('.', "::")
-        }
+        self.name_mangler.namespace_cpp(string)
     }
     pub fn name_cpp(&self, string: &str) -> String {
-        // Coincidentally the same as path_name
-        string.replace(['<', '`', '>', '/', '.'], "_")
+        self.name_mangler.name_cpp(string)
     }
     pub fn namespace_path(&self, string: &str) -> String {
-        string.replace(['<', '>', '`', '/'], "_").replace('.', "/")
+        self.name_mangler.namespace_path(string)
     }
     pub fn path_name(&self, string: &str) -> String {
-        string.replace(['<', '>', '`', '.', '/'], "_")
+        self.name_mangler.path_name(string)
+    }
+    pub fn is_namespace_excluded(&self, namespace: &str) -> bool {
+        self.exclude_namespaces
+            .iter()
+            .any(|excluded| is_in_namespace(namespace, excluded))
+    }
+    /// Compiler generated names are wrapped in angle brackets, e.g. <Foo>k__BackingField
+    pub fn is_hidden_compiler_generated(&self, name: &str) -> bool {
+        self.hide_compiler_generated && name.starts_with('<')
+    }
+    /// Root generated headers are included relative to, see angle_includes
+    pub fn include_root(&self) -> Option<PathBuf> {
+        self.angle_includes.then(|| self.header_path.clone())
+    }
+    /// Path of a header cordl writes alongside the types, e.g. cordl/is_convertible
+    pub fn support_header_path(&self, name: &str) -> PathBuf {
+        self.header_path
+            .join(format!("{name}.{}", self.header_extension))
+    }
+    /// Shared header declaring the primary cordl::is_convertible template
+    pub fn convertible_traits_path(&self) -> PathBuf {
+        self.support_header_path("cordl/is_convertible")
+    }
+    /// Directory an assembly's headers are written under, without the extension
+    pub fn assembly_path(&self, image_name: &str) -> String {
+        let name = image_name.strip_suffix(".dll").unwrap_or(image_name);
+        self.path_name(name)
+    }
+    /// Directory or file name prefix a namespace's headers are written under
+    pub fn namespace_file_prefix(&self, string: &str) -> String {
+        let namespace = if string.is_empty() {
+            "GlobalNamespace"
+        } else {
+            string
+        };
+
+        // Mangled names never contain a '.', so unlike '_' it can't make A.B + C
+        // and A + B_C collide
+        match self.flat_namespace_paths {
+            true => format!("{}.", self.namespace_path(namespace).replace('/', ".")),
+            false => format!("{}/", self.namespace_path(namespace)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespace_contains_itself() {
+        assert!(is_in_namespace("System", "System"));
+    }
+
+    #[test]
+    fn namespace_contains_sub_namespaces() {
+        assert!(is_in_namespace("System.Collections", "System"));
+        assert!(is_in_namespace("System.Collections.Generic", "System"));
+    }
+
+    #[test]
+    fn namespace_does_not_contain_prefixed_names() {
+        assert!(!is_in_namespace("SystemFoo", "System"));
+        assert!(!is_in_namespace("System", "System.Collections"));
+        assert!(!is_in_namespace("", "System"));
     }
 }
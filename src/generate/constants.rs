@@ -3,12 +3,29 @@ use brocolib::{
     runtime_metadata::Il2CppType,
 };
 
+// sizeof(Il2CppObject)
+// TODO: We assume 64 bit
+pub const OBJECT_SIZE: u32 = 0x10;
+pub const POINTER_SIZE: u32 = 0x8;
+
 pub const TYPE_ATTRIBUTE_INTERFACE: u32 = 0x00000020;
 pub const TYPE_ATTRIBUTE_NESTED_PUBLIC: u32 = 0x00000002;
+pub const TYPE_ATTRIBUTE_SEALED: u32 = 0x00000100;
 
-pub const FIELD_ATTRIBUTE_PUBLIC: u16 = 0x0006;
+// Il2CppTypeDefinition::bitfield
+pub const TYPE_DEFINITION_PACKING_SIZE_SHIFT: u32 = 6;
+pub const TYPE_DEFINITION_PACKING_SIZE_IS_DEFAULT: u32 = 1 << 10;
+
+pub const FIELD_ATTRIBUTE_FIELD_ACCESS_MASK: u16 = 0x0007;
+pub const FIELD_ATTRIBUTE_COMPILER_CONTROLLED: u16 = 0x0000;
 pub const FIELD_ATTRIBUTE_PRIVATE: u16 = 0x0001;
+pub const FIELD_ATTRIBUTE_FAM_AND_ASSEM: u16 = 0x0002;
+pub const FIELD_ATTRIBUTE_ASSEMBLY: u16 = 0x0003;
+pub const FIELD_ATTRIBUTE_FAMILY: u16 = 0x0004;
+pub const FIELD_ATTRIBUTE_FAM_OR_ASSEM: u16 = 0x0005;
+pub const FIELD_ATTRIBUTE_PUBLIC: u16 = 0x0006;
 pub const FIELD_ATTRIBUTE_STATIC: u16 = 0x0010;
+pub const FIELD_ATTRIBUTE_INIT_ONLY: u16 = 0x0020;
 pub const FIELD_ATTRIBUTE_LITERAL: u16 = 0x0040;
 
 pub const METHOD_ATTRIBUTE_PUBLIC: u16 = 0x0006;
@@ -19,6 +36,10 @@ pub const METHOD_ATTRIBUTE_HIDE_BY_SIG: u16 = 0x0080;
 pub const METHOD_ATTRIBUTE_ABSTRACT: u16 = 0x0400;
 pub const METHOD_ATTRIBUTE_SPECIAL_NAME: u16 = 0x0800;
 
+pub const GENERIC_PARAMETER_ATTRIBUTE_REFERENCE_TYPE_CONSTRAINT: u16 = 0x0004;
+pub const GENERIC_PARAMETER_ATTRIBUTE_NOT_NULLABLE_VALUE_TYPE_CONSTRAINT: u16 = 0x0008;
+pub const GENERIC_PARAMETER_ATTRIBUTE_DEFAULT_CONSTRUCTOR_CONSTRAINT: u16 = 0x0010;
+
 pub trait MethodDefintionExtensions {
     fn is_public_method(&self) -> bool;
     fn is_abstract_method(&self) -> bool;
@@ -66,7 +87,10 @@ pub trait ParameterDefinitionExtensions {
 pub trait TypeExtentions {
     fn is_static(&self) -> bool;
     fn is_const(&self) -> bool;
+    fn is_init_only(&self) -> bool;
+    fn is_readonly(&self) -> bool;
     fn is_byref(&self) -> bool;
+    fn field_access(&self) -> &'static str;
 }
 
 impl TypeExtentions for Il2CppType {
@@ -79,14 +103,57 @@ impl TypeExtentions for Il2CppType {
         (self.attrs & FIELD_ATTRIBUTE_LITERAL) != 0
     }
 
+    // FIELD_ATTRIBUTE_INIT_ONLY, C# readonly
+    fn is_init_only(&self) -> bool {
+        (self.attrs & FIELD_ATTRIBUTE_INIT_ONLY) != 0
+    }
+
+    fn is_readonly(&self) -> bool {
+        is_readonly_field(self.attrs)
+    }
+
     fn is_byref(&self) -> bool {
         self.byref
     }
+
+    // C# access modifier of a field
+    fn field_access(&self) -> &'static str {
+        match self.attrs & FIELD_ATTRIBUTE_FIELD_ACCESS_MASK {
+            FIELD_ATTRIBUTE_COMPILER_CONTROLLED => "compilercontrolled",
+            FIELD_ATTRIBUTE_PRIVATE => "private",
+            FIELD_ATTRIBUTE_FAM_AND_ASSEM => "private protected",
+            FIELD_ATTRIBUTE_ASSEMBLY => "internal",
+            FIELD_ATTRIBUTE_FAMILY => "protected",
+            FIELD_ATTRIBUTE_FAM_OR_ASSEM => "protected internal",
+            FIELD_ATTRIBUTE_PUBLIC => "public",
+            _ => "unknown",
+        }
+    }
+}
+
+// Neither const nor readonly fields can be written to
+fn is_readonly_field(attrs: u16) -> bool {
+    attrs & (FIELD_ATTRIBUTE_LITERAL | FIELD_ATTRIBUTE_INIT_ONLY) != 0
+}
+
+// Il2CppTypeDefinition::bitfield
+fn packing_from_bitfield(bitfield: u32) -> Option<u32> {
+    if bitfield & TYPE_DEFINITION_PACKING_SIZE_IS_DEFAULT != 0 {
+        return None;
+    }
+
+    // Stored as 0 = default, 1 = 1, 2 = 2, 3 = 4, 4 = 8, ...
+    match (bitfield >> TYPE_DEFINITION_PACKING_SIZE_SHIFT) & 0xF {
+        0 => None,
+        n => Some(1 << (n - 1)),
+    }
 }
 
 pub trait TypeDefinitionExtensions {
     fn is_value_type(&self) -> bool;
     fn is_enum_type(&self) -> bool;
+    fn is_sealed(&self) -> bool;
+    fn packing(&self) -> Option<u32>;
 }
 
 impl TypeDefinitionExtensions for Il2CppTypeDefinition {
@@ -97,4 +164,69 @@ impl TypeDefinitionExtensions for Il2CppTypeDefinition {
     fn is_enum_type(&self) -> bool {
         self.bitfield & 2 != 0
     }
+
+    // Value types are always sealed
+    fn is_sealed(&self) -> bool {
+        self.flags & TYPE_ATTRIBUTE_SEALED != 0
+    }
+
+    // StructLayout.Pack, None when left as the default
+    fn packing(&self) -> Option<u32> {
+        packing_from_bitfield(self.bitfield)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packed(n: u32) -> u32 {
+        n << TYPE_DEFINITION_PACKING_SIZE_SHIFT
+    }
+
+    #[test]
+    fn packing_default() {
+        assert_eq!(packing_from_bitfield(0), None);
+        assert_eq!(
+            packing_from_bitfield(TYPE_DEFINITION_PACKING_SIZE_IS_DEFAULT),
+            None
+        );
+        // The flag wins over a stored size
+        assert_eq!(
+            packing_from_bitfield(TYPE_DEFINITION_PACKING_SIZE_IS_DEFAULT | packed(3)),
+            None
+        );
+    }
+
+    #[test]
+    fn packing_sizes() {
+        assert_eq!(packing_from_bitfield(packed(1)), Some(1));
+        assert_eq!(packing_from_bitfield(packed(2)), Some(2));
+        assert_eq!(packing_from_bitfield(packed(3)), Some(4));
+        assert_eq!(packing_from_bitfield(packed(4)), Some(8));
+        assert_eq!(packing_from_bitfield(packed(8)), Some(128));
+    }
+
+    #[test]
+    fn packing_ignores_other_bits() {
+        // value type and enum bits
+        assert_eq!(packing_from_bitfield(packed(4) | 0b11), Some(8));
+    }
+
+    #[test]
+    fn readonly_fields() {
+        assert!(is_readonly_field(FIELD_ATTRIBUTE_LITERAL));
+        assert!(is_readonly_field(FIELD_ATTRIBUTE_INIT_ONLY));
+        assert!(is_readonly_field(
+            FIELD_ATTRIBUTE_STATIC | FIELD_ATTRIBUTE_INIT_ONLY | FIELD_ATTRIBUTE_PUBLIC
+        ));
+    }
+
+    #[test]
+    fn writable_fields() {
+        assert!(!is_readonly_field(0));
+        assert!(!is_readonly_field(
+            FIELD_ATTRIBUTE_STATIC | FIELD_ATTRIBUTE_PUBLIC
+        ));
+    }
 }
@@ -1,7 +1,11 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::BTreeMap,
     fs::{create_dir_all, remove_file, File},
+    io::Write,
+    panic::{self, AssertUnwindSafe},
     path::{Path, PathBuf},
+    sync::atomic::Ordering,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use brocolib::global_metadata::TypeDefinitionIndex;
@@ -9,14 +13,16 @@ use color_eyre::eyre::ContextCompat;
 
 use brocolib::runtime_metadata::TypeData;
 use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
 
-use crate::generate::members::CppInclude;
+use crate::generate::members::{CppInclude, CppMember};
 
 use super::{
     config::GenerationConfig,
     cpp_type::CppType,
-    cs_type::CSType,
-    metadata::Metadata,
+    cs_type::{CSType, FAILED_TYPES_WARNING, UNKNOWN_TYPE_COUNT, WARNINGS},
+    metadata::{Metadata, TypeHandlerFn},
     writer::{CppWriter, Writable},
 };
 
@@ -29,9 +35,13 @@ pub struct CppContext {
 
     // combined header
     pub fundamental_path: PathBuf,
+    // forward declares of all types in the context, when written separately
+    pub fwd_path: Option<PathBuf>,
+    // header directory includes of the context are written relative to, in angle brackets
+    pub include_root: Option<PathBuf>,
 
     // Types to write, typedef
-    typedef_types: HashMap<TypeData, CppType>,
+    typedef_types: FxHashMap<TypeData, CppType>,
 }
 
 impl CppContext {
@@ -67,10 +77,53 @@ impl CppContext {
         &self.typedef_path
     }
 
-    pub fn get_types(&self) -> &HashMap<TypeData, CppType> {
+    /// Include of one of this context's headers, in the configured style
+    pub fn include(&self, path: &Path) -> CppInclude {
+        CppInclude::new_generated(path.to_path_buf(), self.include_root.clone())
+    }
+
+    pub fn get_types(&self) -> &FxHashMap<TypeData, CppType> {
         &self.typedef_types
     }
 
+    /// Context with its paths but no types
+    fn make_empty(
+        metadata: &Metadata,
+        config: &GenerationConfig,
+        tdi: TypeDefinitionIndex,
+    ) -> CppContext {
+        let t = &metadata.metadata.global_metadata.type_definitions[tdi];
+        let ns = t.namespace(metadata.metadata);
+        let name = t.name(metadata.metadata);
+
+        let mut path = config.namespace_file_prefix(ns);
+        if config.group_by_assembly
+            && let Some(image_name) = metadata.tdi_to_image_name.get(&tdi)
+        {
+            path = format!("{}/{path}", config.assembly_path(image_name));
+        }
+        let path_name = config.path_name(name);
+        let ext = &config.header_extension;
+        CppContext {
+            typedef_path: config
+                .header_path
+                .join(format!("{path}__{path_name}_def.{ext}")),
+            type_impl_path: config
+                .header_path
+                .join(format!("{path}__{path_name}_impl.{ext}")),
+            fundamental_path: config
+                .header_path
+                .join(format!("{path}{path_name}.{ext}")),
+            fwd_path: config.forward_declare_headers.then(|| {
+                config
+                    .header_path
+                    .join(format!("{path}__{path_name}_fwd.{ext}"))
+            }),
+            include_root: config.include_root(),
+            typedef_types: Default::default(),
+        }
+    }
+
     // TODO: Move out, this is CSContext
     fn make(
         metadata: &Metadata,
@@ -82,30 +135,7 @@ impl CppContext {
         let ns = t.namespace(metadata.metadata);
         let name = t.name(metadata.metadata);
 
-        let ns_path = config.namespace_path(ns);
-        let path = if ns_path.is_empty() {
-            "GlobalNamespace/".to_string()
-        } else {
-            ns_path + "/"
-        };
-        let mut x = CppContext {
-            typedef_path: config.header_path.join(format!(
-                "{}__{}_def.hpp",
-                path,
-                &config.path_name(name)
-            )),
-            type_impl_path: config.header_path.join(format!(
-                "{}__{}_impl.hpp",
-                path,
-                &config.path_name(name)
-            )),
-            fundamental_path: config.header_path.join(format!(
-                "{}{}.hpp",
-                path,
-                &config.path_name(name)
-            )),
-            typedef_types: Default::default(),
-        };
+        let mut x = CppContext::make_empty(metadata, config, tdi);
         match CppType::make_cpp_type(metadata, config, tag) {
             Some(cpptype) => {
                 x.typedef_types
@@ -159,19 +189,35 @@ impl CppContext {
             .values()
             .flat_map(|t| &t.requirements.required_includes)
             .unique()
+            .sorted()
             .try_for_each(|i| i.write(&mut typedef_writer))?;
 
         // write forward declares
         {
-            self.typedef_types
+            let mut forward_declares = self
+                .typedef_types
                 .values()
                 .flat_map(|t| &t.requirements.forward_declares)
                 .map(|(d, _)| d)
-                .unique()
-                // TODO: Check forward declare is not of own type
-                .try_for_each(|i| i.write(&mut typedef_writer))?;
+                .unique();
+
+            // TODO: Check forward declare is not of own type
+            match &self.fwd_path {
+                Some(fwd_path) => {
+                    let mut fwd_writer = CppWriter {
+                        stream: File::create(fwd_path.as_path())?,
+                        indent: 0,
+                        newline: true,
+                    };
+                    writeln!(fwd_writer, "#pragma once")?;
+                    forward_declares.try_for_each(|i| i.write(&mut fwd_writer))?;
+
+                    self.include(fwd_path).write(&mut typedef_writer)?;
+                }
+                None => forward_declares.try_for_each(|i| i.write(&mut typedef_writer))?,
+            }
 
-            CppInclude::new(self.type_impl_path.to_path_buf()).write(&mut typeimpl_writer)?;
+            self.include(&self.type_impl_path).write(&mut typeimpl_writer)?;
             // This is likely not necessary
             // self.typedef_types
             //     .values()
@@ -190,19 +236,42 @@ impl CppContext {
             t.write_impl(&mut typeimpl_writer)?;
         }
 
-        CppInclude::new(self.typedef_path.to_path_buf()).write(&mut fundamental_writer)?;
-        CppInclude::new(self.type_impl_path.to_path_buf()).write(&mut fundamental_writer)?;
+        self.include(&self.typedef_path).write(&mut fundamental_writer)?;
+        self.include(&self.type_impl_path).write(&mut fundamental_writer)?;
 
         // TODO: Write type impl and fundamental files here
         Ok(())
     }
 }
 
+/// Counts of generated types and members, written by `write_stats_json`
+#[derive(Serialize)]
+struct GenerationStats<'a> {
+    contexts: usize,
+    types: usize,
+    value_types: usize,
+    reference_types: usize,
+    /// Instantiations of generic types known to the metadata
+    generic_types: usize,
+    filled_types: usize,
+    excluded_types: usize,
+    /// Types that panicked while generating, see --keep-going
+    failed_types: usize,
+    /// Types that generated with a warning, e.g. a void* placeholder
+    errored_types: usize,
+    fields: usize,
+    methods: usize,
+    properties: usize,
+    constructors: usize,
+    unknown_types: usize,
+    timings_ms: BTreeMap<&'a str, u128>,
+}
+
 pub struct CppContextCollection {
-    all_contexts: HashMap<TypeData, CppContext>,
-    alias_context: HashMap<TypeData, TypeData>,
-    filled_types: HashSet<TypeData>,
-    filling_types: HashSet<TypeData>,
+    all_contexts: FxHashMap<TypeData, CppContext>,
+    alias_context: FxHashMap<TypeData, TypeData>,
+    filled_types: FxHashSet<TypeData>,
+    filling_types: FxHashSet<TypeData>,
 }
 
 impl CppContextCollection {
@@ -235,14 +304,22 @@ impl CppContextCollection {
         if let Some((t, mut cpp_type)) = cpp_type_entry {
             assert!(!cpp_type.nested, "Cannot fill a nested type!");
 
-            cpp_type.fill_from_il2cpp(metadata, config, self, tdi);
+            let filled = panic::catch_unwind(AssertUnwindSafe(|| {
+                cpp_type.fill_from_il2cpp(metadata, config, self, tdi)
+            }));
 
-            // Move ownership back up
+            // Move ownership back up, even when partially filled
             self.all_contexts
                 .get_mut(&context_tag)
                 .expect("No cpp context")
                 .typedef_types
                 .insert(t, cpp_type);
+
+            if let Err(payload) = filled {
+                self.filled_types.insert(type_tag);
+                self.filling_types.remove(&type_tag);
+                panic::resume_unwind(payload);
+            }
         }
 
         self.filled_types.insert(type_tag);
@@ -277,16 +354,24 @@ impl CppContextCollection {
         // we can clone
         // sad inefficient memory usage but oh well
         let mut nested_types = owner.nested_types.clone();
-        nested_types.iter_mut().for_each(|nested_type| {
+        let mut failure = None;
+        for nested_type in nested_types.iter_mut() {
             let nested_tag = nested_type.self_tag;
             self.filling_types.insert(nested_tag);
             let tdi = CppType::get_tag_tdi(nested_tag);
 
-            nested_type.fill_from_il2cpp(metadata, config, self, tdi);
+            let filled = panic::catch_unwind(AssertUnwindSafe(|| {
+                nested_type.fill_from_il2cpp(metadata, config, self, tdi)
+            }));
 
             self.filled_types.insert(nested_tag);
             self.filling_types.remove(&nested_tag);
-        });
+
+            if let Err(payload) = filled {
+                failure = Some(payload);
+                break;
+            }
+        }
         // nested_tags.into_iter().for_each(|nested_tag| {
         //     self.filling_types.insert(nested_tag);
 
@@ -302,7 +387,11 @@ impl CppContextCollection {
         //     self.filling_types.remove(&nested_tag);
         // });
 
+        // Keep what was filled so far, even on failure the owner must not lose its nested types
         self.get_cpp_type_mut(owner_type_tag).unwrap().nested_types = nested_types;
+        if let Some(payload) = failure {
+            panic::resume_unwind(payload);
+        }
     }
 
     pub fn get_context_root_tag(&self, ty: TypeData) -> TypeData {
@@ -340,7 +429,18 @@ impl CppContextCollection {
         }
 
         let tdi = CppType::get_tag_tdi(context_root_tag);
-        let context = CppContext::make(metadata, config, tdi, context_root_tag);
+        let made = panic::catch_unwind(AssertUnwindSafe(|| {
+            CppContext::make(metadata, config, tdi, context_root_tag)
+        }));
+        let context = match made {
+            Ok(context) => context,
+            Err(payload) => {
+                // Leave an empty context so types referencing this one can still resolve it
+                let empty = CppContext::make_empty(metadata, config, tdi);
+                self.all_contexts.insert(context_root_tag, empty);
+                panic::resume_unwind(payload);
+            }
+        };
         // Now do children
         for cpp_type in context.typedef_types.values() {
             self.alias_nested_types(cpp_type, cpp_type.self_tag);
@@ -389,7 +489,452 @@ impl CppContextCollection {
             alias_context: Default::default(),
         }
     }
-    pub fn get(&self) -> &HashMap<TypeData, CppContext> {
+    pub fn get(&self) -> &FxHashMap<TypeData, CppContext> {
         &self.all_contexts
     }
+
+    /// Runs the global type handlers over every type, declaring types before their nested types.
+    /// This happens after filling, so per type handlers have already run
+    pub fn apply_global_handlers(&mut self, handlers: &[TypeHandlerFn]) {
+        fn apply(cpp_type: &mut CppType, handlers: &[TypeHandlerFn]) {
+            handlers.iter().for_each(|h| h(cpp_type));
+            cpp_type
+                .nested_types
+                .iter_mut()
+                .for_each(|n| apply(n, handlers));
+        }
+
+        self.all_contexts
+            .values_mut()
+            .flat_map(|c| c.typedef_types.values_mut())
+            .filter(|t| !t.nested)
+            .for_each(|t| apply(t, handlers));
+    }
+
+    /// Writes a header mapping every type's C++ name to its instance size, see CppType::instance_size
+    pub fn write_size_table(&self, path: &Path) -> color_eyre::Result<()> {
+        let sizes = self
+            .all_contexts
+            .values()
+            .flat_map(|c| c.typedef_types.values())
+            .flat_map(|t| {
+                t.nested_types_flattened()
+                    .into_values()
+                    .chain(std::iter::once(t))
+                    .collect_vec()
+            })
+            .filter_map(|t| Some((t.formatted_complete_cpp_name(), t.instance_size?)))
+            .sorted()
+            .collect_vec();
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        println!("Writing size table {path:?}");
+        let mut writer = CppWriter {
+            stream: File::create(path)?,
+            indent: 0,
+            newline: true,
+        };
+
+        writeln!(writer, "#pragma once")?;
+        CppInclude::new_system("array".into()).write(&mut writer)?;
+        CppInclude::new_system("cstddef".into()).write(&mut writer)?;
+        CppInclude::new_system("string_view".into()).write(&mut writer)?;
+        CppInclude::new_system("utility".into()).write(&mut writer)?;
+
+        writeln!(writer, "namespace cordl {{")?;
+        writeln!(writer, "// C++ type name to il2cpp instance size")?;
+        writeln!(
+            writer,
+            "inline constexpr ::std::array<::std::pair<::std::string_view, ::std::size_t>, {}> TYPE_SIZES{{{{",
+            sizes.len()
+        )?;
+        for (name, size) in sizes {
+            writeln!(writer, "  {{\"{name}\", 0x{size:x}}},")?;
+        }
+        writeln!(writer, "}}}};")?;
+        writeln!(writer, "}} // namespace cordl")?;
+
+        Ok(())
+    }
+
+    /// Writes a source file mapping C# full names to classof accessors, along with its header
+    pub fn write_type_registry(
+        &self,
+        metadata: &Metadata,
+        config: &GenerationConfig,
+    ) -> color_eyre::Result<()> {
+        let mut entries = vec![];
+        let mut includes = vec![];
+        for context in self.all_contexts.values() {
+            let types = context
+                .typedef_types
+                .values()
+                .flat_map(|t| {
+                    t.nested_types_flattened()
+                        .into_values()
+                        .chain(std::iter::once(t))
+                        .collect_vec()
+                })
+                // Open generics have no single class
+                .filter(|t| t.generic_args.names.is_empty())
+                .collect_vec();
+
+            if types.is_empty() {
+                continue;
+            }
+
+            includes.push(context.include(&context.typedef_path));
+            entries.extend(types.into_iter().map(|t| {
+                let tdi = CppType::get_tag_tdi(t.self_tag);
+                let full_name = metadata.metadata.global_metadata.type_definitions[tdi]
+                    .full_name(metadata.metadata, false);
+                (full_name, t.classof_cpp_name(config))
+            }));
+        }
+        entries.sort();
+
+        let header_path = config.support_header_path("cordl/type_registry");
+        let source_path = config.source_path.join("cordl/type_registry.cpp");
+        for path in [&header_path, &source_path] {
+            if let Some(parent) = path.parent() {
+                create_dir_all(parent)?;
+            }
+        }
+
+        println!("Writing type registry {header_path:?}");
+        let mut writer = CppWriter {
+            stream: File::create(&header_path)?,
+            indent: 0,
+            newline: true,
+        };
+        writeln!(writer, "#pragma once")?;
+        CppInclude::new_system("string_view".into()).write(&mut writer)?;
+        CppInclude::new_system("unordered_map".into()).write(&mut writer)?;
+        writeln!(writer, "struct Il2CppClass;")?;
+        writeln!(writer, "namespace cordl {{")?;
+        writeln!(writer, "// C# full name to the generated type's classof accessor")?;
+        writeln!(
+            writer,
+            "extern const ::std::unordered_map<::std::string_view, Il2CppClass* (*)()> TYPE_REGISTRY;"
+        )?;
+        writeln!(writer, "}} // namespace cordl")?;
+
+        println!("Writing type registry {source_path:?}");
+        let mut writer = CppWriter {
+            stream: File::create(&source_path)?,
+            indent: 0,
+            newline: true,
+        };
+        CppInclude::new_generated(header_path, config.include_root()).write(&mut writer)?;
+        includes
+            .iter()
+            .sorted()
+            .try_for_each(|i| i.write(&mut writer))?;
+        writeln!(writer, "namespace cordl {{")?;
+        writeln!(
+            writer,
+            "const ::std::unordered_map<::std::string_view, Il2CppClass* (*)()> TYPE_REGISTRY{{"
+        )?;
+        for (full_name, classof) in entries {
+            writeln!(writer, "  {{\"{full_name}\", &{classof}}},")?;
+        }
+        writeln!(writer, "}};")?;
+        writeln!(writer, "}} // namespace cordl")?;
+
+        Ok(())
+    }
+
+    /// Writes `using Old = New;` aliases for renamed types, read as old=new C# full name lines
+    pub fn write_rename_shims(
+        &self,
+        metadata: &Metadata,
+        config: &GenerationConfig,
+        renames: &str,
+    ) -> color_eyre::Result<()> {
+        let mut types_by_name: FxHashMap<String, (&CppContext, &CppType)> =
+            FxHashMap::default();
+        for context in self.all_contexts.values() {
+            for t in context.typedef_types.values().flat_map(|t| {
+                t.nested_types_flattened()
+                    .into_values()
+                    .chain(std::iter::once(t))
+                    .collect_vec()
+            }) {
+                let tdi = CppType::get_tag_tdi(t.self_tag);
+                let full_name = metadata.metadata.global_metadata.type_definitions[tdi]
+                    .full_name(metadata.metadata, false);
+                types_by_name.insert(full_name, (context, t));
+            }
+        }
+
+        let mut includes = vec![];
+        let mut aliases = vec![];
+        for line in renames.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let Some((old_name, new_name)) = line.split_once('=') else {
+                println!("Skipping rename `{line}`, expected old=new");
+                continue;
+            };
+            let Some((context, new_ty)) = types_by_name.get(new_name.trim()) else {
+                println!("Skipping rename `{line}`, no type named {new_name}");
+                continue;
+            };
+
+            let old_name = old_name.trim();
+            let (old_ns, old_name) = old_name.rsplit_once('.').unwrap_or(("", old_name));
+            let template = &new_ty.generic_args.names;
+            let alias = match template.is_empty() {
+                true => format!(
+                    "using {} = ::{};",
+                    config.name_cpp(old_name),
+                    new_ty.formatted_complete_cpp_name()
+                ),
+                false => format!(
+                    "template<{}>\nusing {} = ::{}<{}>;",
+                    template.iter().map(|s| format!("typename {s}")).join(","),
+                    config.name_cpp(old_name),
+                    new_ty.formatted_complete_cpp_name(),
+                    template.join(",")
+                ),
+            };
+
+            includes.push(context.include(&context.typedef_path));
+            aliases.push((config.namespace_cpp(old_ns), alias));
+        }
+
+        let path = config.support_header_path("cordl/renames");
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        println!("Writing rename shims {path:?}");
+        let mut writer = CppWriter {
+            stream: File::create(&path)?,
+            indent: 0,
+            newline: true,
+        };
+
+        writeln!(writer, "#pragma once")?;
+        includes
+            .iter()
+            .unique()
+            .sorted()
+            .try_for_each(|i| i.write(&mut writer))?;
+        for (namespace, alias) in aliases {
+            writeln!(writer, "namespace {namespace} {{")?;
+            writeln!(writer, "{alias}")?;
+            writeln!(writer, "}} // namespace {namespace}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes counts of generated types and members, plus phase timings, as JSON
+    pub fn write_stats_json(
+        &self,
+        metadata: &Metadata,
+        path: &Path,
+        excluded_types: usize,
+        timings: &[(&str, u128)],
+    ) -> color_eyre::Result<()> {
+        let all_types = self
+            .all_contexts
+            .values()
+            .flat_map(|c| c.typedef_types.values())
+            .flat_map(|t| {
+                t.nested_types_flattened()
+                    .into_values()
+                    .chain(std::iter::once(t))
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let value_types = all_types.iter().filter(|t| t.is_value_type).count();
+        let generic_types = metadata.metadata_registration.generic_classes.len();
+
+        let warnings = WARNINGS.lock().unwrap();
+        let failed = warnings
+            .get(FAILED_TYPES_WARNING)
+            .into_iter()
+            .flatten()
+            .collect::<FxHashSet<_>>();
+        let errored_types = warnings
+            .iter()
+            .filter(|(category, _)| **category != FAILED_TYPES_WARNING)
+            .flat_map(|(_, type_names)| type_names)
+            .filter(|name| !failed.contains(name))
+            .unique()
+            .count();
+        let members = all_types.iter().flat_map(|t| &t.declarations).collect_vec();
+        let count_members = |f: fn(&CppMember) -> bool| members.iter().filter(|m| f(m)).count();
+
+        let stats = GenerationStats {
+            contexts: self.all_contexts.len(),
+            types: all_types.len(),
+            value_types,
+            reference_types: all_types.len() - value_types,
+            generic_types,
+            filled_types: self.filled_types.len(),
+            excluded_types,
+            failed_types: failed.len(),
+            errored_types,
+            fields: count_members(|m| matches!(m, CppMember::Field(_))),
+            methods: count_members(|m| matches!(m, CppMember::MethodDecl(_))),
+            properties: count_members(|m| matches!(m, CppMember::Property(_))),
+            constructors: count_members(|m| matches!(m, CppMember::ConstructorDecl(_))),
+            unknown_types: UNKNOWN_TYPE_COUNT.load(Ordering::Relaxed),
+            timings_ms: timings.iter().copied().collect(),
+        };
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        println!("Writing stats {path:?}");
+        serde_json::to_writer_pretty(File::create(path)?, &stats)?;
+
+        Ok(())
+    }
+
+    /// Writes which cordl version and metadata the headers were generated from
+    pub fn write_info(path: &Path, metadata_hash: u64, timestamp: bool) -> color_eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        println!("Writing cordl info {path:?}");
+        let mut writer = CppWriter {
+            stream: File::create(path)?,
+            indent: 0,
+            newline: true,
+        };
+
+        writeln!(writer, "#pragma once")?;
+        CppInclude::new_system("cstddef".into()).write(&mut writer)?;
+        CppInclude::new_system("cstdint".into()).write(&mut writer)?;
+
+        writeln!(writer, "namespace cordl {{")?;
+        writeln!(
+            writer,
+            "inline constexpr const char VERSION[] = \"{}\";",
+            env!("CARGO_PKG_VERSION")
+        )?;
+        writeln!(writer, "// FNV-1a of the global-metadata.dat the headers were made from")?;
+        writeln!(writer, "inline constexpr ::std::uint64_t METADATA_HASH = 0x{metadata_hash:x};")?;
+        // TODO: We assume 64 bit
+        writeln!(writer, "inline constexpr ::std::size_t POINTER_SIZE = 8;")?;
+        // Opt in, the headers are otherwise the same for the same inputs
+        if timestamp {
+            let generated_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            writeln!(writer, "// Unix time of generation")?;
+            writeln!(writer, "inline constexpr ::std::uint64_t GENERATED_AT = {generated_at};")?;
+        }
+        writeln!(writer, "}} // namespace cordl")?;
+
+        Ok(())
+    }
+
+    /// Writes the primary template the generated is_convertible specializations refine
+    pub fn write_convertible_traits(path: &Path) -> color_eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        println!("Writing convertible traits {path:?}");
+        let mut writer = CppWriter {
+            stream: File::create(path)?,
+            indent: 0,
+            newline: true,
+        };
+
+        writeln!(writer, "#pragma once")?;
+        CppInclude::new_system("type_traits".into()).write(&mut writer)?;
+
+        writeln!(writer, "namespace cordl {{")?;
+        writeln!(writer, "// True when From derives from or implements To")?;
+        writeln!(writer, "template<typename From, typename To>")?;
+        writeln!(writer, "struct is_convertible : ::std::false_type {{}};")?;
+        writeln!(writer, "template<typename T>")?;
+        writeln!(writer, "struct is_convertible<T, T> : ::std::true_type {{}};")?;
+        writeln!(writer, "template<typename From, typename To>")?;
+        writeln!(
+            writer,
+            "inline constexpr bool is_convertible_v = is_convertible<From, To>::value;"
+        )?;
+        writeln!(writer, "}} // namespace cordl")?;
+
+        Ok(())
+    }
+
+    /// Checks every generated include and forward declare resolves to a type in the collection
+    /// Returns the number of unresolved references
+    pub fn validate(&self, config: &GenerationConfig) -> usize {
+        let all_types = self
+            .all_contexts
+            .values()
+            .flat_map(|c| c.typedef_types.values())
+            .flat_map(|t| {
+                t.nested_types_flattened()
+                    .into_values()
+                    .chain(std::iter::once(t))
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let known_paths: FxHashSet<&Path> = self
+            .all_contexts
+            .values()
+            .flat_map(|c| {
+                [
+                    c.typedef_path.as_path(),
+                    c.type_impl_path.as_path(),
+                    c.fundamental_path.as_path(),
+                ]
+            })
+            .collect();
+        let known_types: FxHashSet<(&str, &str)> = all_types
+            .iter()
+            .copied()
+            .map(|t| (t.cpp_namespace(), t.name().as_str()))
+            .collect();
+
+        let mut unresolved = 0;
+        for t in all_types {
+            for include in &t.requirements.required_includes {
+                // Only includes of generated headers can be checked
+                if include.system || !include.include.starts_with(&config.header_path) {
+                    continue;
+                }
+
+                if !known_paths.contains(include.include.as_path()) {
+                    println!(
+                        "Unresolved include {:?} in type {}",
+                        include.include,
+                        t.formatted_complete_cpp_name()
+                    );
+                    unresolved += 1;
+                }
+            }
+
+            for (fd, include) in &t.requirements.forward_declares {
+                let namespace = fd.namespace.as_deref().unwrap_or_default();
+
+                if !known_types.contains(&(namespace, fd.name.as_str()))
+                    || !known_paths.contains(include.include.as_path())
+                {
+                    println!(
+                        "Unresolved forward declare {namespace}::{} ({:?}) in type {}",
+                        fd.name,
+                        include.include,
+                        t.formatted_complete_cpp_name()
+                    );
+                    unresolved += 1;
+                }
+            }
+        }
+
+        unresolved
+    }
 }
@@ -1,13 +1,10 @@
-use std::{
-    collections::{HashMap, HashSet},
-    io::Write,
-    rc::Rc,
-};
+use std::{collections::HashSet, io::Write, rc::Rc};
 
 use color_eyre::eyre::Context;
 
 use brocolib::{global_metadata::TypeDefinitionIndex, runtime_metadata::TypeData};
 use itertools::Itertools;
+use rustc_hash::FxHashMap;
 
 use super::{
     members::{CppForwardDeclare, CppInclude, CppMember, CppTemplate},
@@ -46,8 +43,14 @@ pub struct CppType {
     /// Move to CsType/CppType?
     pub nonmember_implementations: Vec<Rc<dyn Writable>>,
     pub nonmember_declarations: Vec<Rc<dyn Writable>>,
+    /// After the type's namespace is closed, e.g. specializations of templates in other namespaces
+    pub global_declarations: Vec<Rc<dyn Writable>>,
 
     pub is_value_type: bool,
+    /// Size of an instance, including the object header for reference types only
+    pub instance_size: Option<u32>,
+    /// Explicit packing of value types, written as #pragma pack
+    pub packing: Option<u32>,
     pub requirements: CppTypeRequirements,
 
     pub inherit: Vec<String>,
@@ -71,6 +74,16 @@ impl CppTypeRequirements {
             "beatsaber-hook/shared/utils/typedefs-string.hpp".into(),
         ));
     }
+    pub fn needs_byref_include(&mut self) {
+        self.required_includes.insert(CppInclude::new(
+            "beatsaber-hook/shared/utils/byref.hpp".into(),
+        ));
+    }
+    pub fn needs_utils_functions_include(&mut self) {
+        self.required_includes.insert(CppInclude::new(
+            "beatsaber-hook/shared/utils/utils-functions.h".into(),
+        ));
+    }
     pub fn needs_arrayw_include(&mut self) {
         self.required_includes.insert(CppInclude::new(
             "beatsaber-hook/shared/utils/typedefs-array".into(),
@@ -95,7 +108,7 @@ impl CppType {
         &self.cpp_name
     }
 
-    pub fn nested_types_flattened(&self) -> HashMap<TypeData, &CppType> {
+    pub fn nested_types_flattened(&self) -> FxHashMap<TypeData, &CppType> {
         self.nested_types
             .iter()
             .flat_map(|n| n.nested_types_flattened())
@@ -209,6 +222,9 @@ impl CppType {
         }
 
         // Write type definition
+        if let Some(packing) = self.packing {
+            writeln!(writer, "#pragma pack(push, {packing})")?;
+        }
         self.generic_args.write(writer)?;
         writeln!(writer, "// Is value type: {}", self.is_value_type)?;
         // Type definition plus inherit lines
@@ -248,9 +264,20 @@ impl CppType {
             "static constexpr bool __CORDL_IS_VALUE_TYPE = {};",
             self.is_value_type
         )?;
+        // Size of the boxed object for reference types, of the data for value types
+        match self.instance_size {
+            Some(size) => writeln!(
+                writer,
+                "static constexpr ::std::size_t __CORDL_INSTANCE_SIZE = 0x{size:x};"
+            )?,
+            None => writeln!(writer, "// TODO: Unknown instance size")?,
+        }
         // Type complete
         writer.dedent();
         writeln!(writer, "}};")?;
+        if self.packing.is_some() {
+            writeln!(writer, "#pragma pack(pop)")?;
+        }
 
         // NON MEMBER DECLARATIONS
         self.nonmember_declarations
@@ -262,6 +289,13 @@ impl CppType {
             writer.dedent();
             writeln!(writer, "}} // namespace {n}")?;
         }
+
+        // Only top level types have their own namespace block to be outside of
+        if namespace.is_some() {
+            self.global_declarations
+                .iter()
+                .try_for_each(|d| d.write(writer))?;
+        }
         // TODO: Write additional meta-info here, perhaps to ensure correct conversions?
         Ok(())
     }
@@ -1,7 +1,11 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet},
     io::{Cursor, Read},
     rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
 };
 
 use brocolib::{
@@ -12,25 +16,160 @@ use brocolib::{
 };
 use byteorder::{LittleEndian, ReadBytesExt};
 use itertools::Itertools;
+use rustc_hash::FxHashMap;
 
 use super::{
     config::GenerationConfig,
     constants::{
         MethodDefintionExtensions, TypeDefinitionExtensions, TypeExtentions,
+        GENERIC_PARAMETER_ATTRIBUTE_DEFAULT_CONSTRUCTOR_CONSTRAINT,
+        GENERIC_PARAMETER_ATTRIBUTE_NOT_NULLABLE_VALUE_TYPE_CONSTRAINT,
+        GENERIC_PARAMETER_ATTRIBUTE_REFERENCE_TYPE_CONSTRAINT, OBJECT_SIZE,
         TYPE_ATTRIBUTE_INTERFACE,
     },
     context::CppContextCollection,
     cpp_type::CppType,
     members::{
-        CppCommentedString, CppConstructorDecl, CppConstructorImpl, CppField, CppForwardDeclare,
-        CppInclude, CppMember, CppMethodData, CppMethodDecl, CppMethodImpl, CppMethodSizeStruct,
-        CppParam, CppProperty, CppTemplate,
+        CppCommentedString, CppConstructorDecl, CppConstructorImpl, CppDirectCall, CppField,
+        CppForwardDeclare, CppInclude, CppLine, CppMember, CppMethodData, CppMethodDecl,
+        CppMethodImpl, CppMethodSizeStruct, CppParam, CppProperty, CppTemplate,
     },
     metadata::Metadata,
 };
 
 type Endian = LittleEndian;
 
+/// Number of types that fell back to a placeholder in cppify_name_il2cpp
+pub static UNKNOWN_TYPE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Types each warning was raised for, keyed by category, summarized at the end of a run
+pub static WARNINGS: Mutex<BTreeMap<&'static str, Vec<String>>> = Mutex::new(BTreeMap::new());
+
+/// Warning category of types that panicked while generating, see --keep-going
+pub const FAILED_TYPES_WARNING: &str = "types failed to generate";
+
+pub fn record_warning(category: &'static str, type_name: String) {
+    WARNINGS
+        .lock()
+        .unwrap()
+        .entry(category)
+        .or_default()
+        .push(type_name);
+}
+
+/// One line per category, e.g. "37 fields inside the object header across 12 types: ..."
+pub fn warning_summary() -> Vec<String> {
+    WARNINGS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(category, type_names)| {
+            let types = type_names.iter().unique().sorted().collect_vec();
+            let shown = types.iter().take(5).join(", ");
+            let more = match types.len() > 5 {
+                true => format!(" and {} more", types.len() - 5),
+                false => "".to_string(),
+            };
+
+            format!(
+                "{} {category} across {} types: {shown}{more}",
+                type_names.len(),
+                types.len()
+            )
+        })
+        .collect()
+}
+
+/// Name of the parameter at index, argN when it's unnamed or repeats an earlier name.
+/// argN skips names that are taken and real names of later parameters
+fn param_name(name: &str, index: usize, real_names: &[&str], taken: &[&str]) -> String {
+    if !name.is_empty() && !taken.contains(&name) {
+        return name.to_string();
+    }
+
+    (index..)
+        .map(|i| format!("arg{i}"))
+        .find(|n| !real_names.contains(&n.as_str()) && !taken.contains(&n.as_str()))
+        .unwrap()
+}
+
+/// Suffixed so large values stay valid literals, e.g. as enumerator initializers
+fn int64_literal(value: i64) -> String {
+    match value {
+        // -9223372036854775808LL negates a literal that doesn't fit
+        i64::MIN => "(-9223372036854775807LL - 1)".to_string(),
+        value => format!("{value}LL"),
+    }
+}
+
+fn uint64_literal(value: u64) -> String {
+    format!("{value}ULL")
+}
+
+/// Enum constant from the value of its backing type, e.g. ::System::DayOfWeek(1)
+fn enum_default_value(cpp_name: &str, backing_value: &str) -> String {
+    format!("{cpp_name}({backing_value})")
+}
+
+/// A generic parameter by name, or void* when il2cpp didn't say which one it is
+fn generic_parameter_cpp_name(name: Option<&str>, typ: &impl std::fmt::Debug) -> String {
+    match name {
+        Some(name) => name.to_string(),
+        None => format!("/* UNKNOWN GENERIC PARAMETER! {typ:?} */ void*"),
+    }
+}
+
+/// C# style, e.g. T: class, IComparable_1<T>, new(). None when unconstrained
+fn generic_constraint(name: &str, flags: u16, types: Vec<String>) -> Option<String> {
+    let mut constraints: Vec<String> = vec![];
+    if flags & GENERIC_PARAMETER_ATTRIBUTE_REFERENCE_TYPE_CONSTRAINT != 0 {
+        constraints.push("class".to_string());
+    }
+    if flags & GENERIC_PARAMETER_ATTRIBUTE_NOT_NULLABLE_VALUE_TYPE_CONSTRAINT != 0 {
+        constraints.push("struct".to_string());
+    }
+    constraints.extend(types);
+    if flags & GENERIC_PARAMETER_ATTRIBUTE_DEFAULT_CONSTRUCTOR_CONSTRAINT != 0 {
+        constraints.push("new()".to_string());
+    }
+
+    if constraints.is_empty() {
+        return None;
+    }
+    Some(format!("{name}: {}", constraints.join(", ")))
+}
+
+/// C++ name of a type that needs no include or declaration of its own
+fn primitive_cpp_name(ty: Il2CppTypeEnum) -> Option<&'static str> {
+    let name = match ty {
+        Il2CppTypeEnum::I1 => "int8_t",
+        Il2CppTypeEnum::I2 => "int16_t",
+        Il2CppTypeEnum::I4 => "int32_t",
+        // TODO: We assume 64 bit
+        Il2CppTypeEnum::I | Il2CppTypeEnum::I8 => "int64_t",
+        Il2CppTypeEnum::U1 => "uint8_t",
+        Il2CppTypeEnum::U2 => "uint16_t",
+        Il2CppTypeEnum::U4 => "uint32_t",
+        // TODO: We assume 64 bit
+        Il2CppTypeEnum::U | Il2CppTypeEnum::U8 => "uint64_t",
+
+        // https://learn.microsoft.com/en-us/nimbusml/concepts/types
+        // https://en.cppreference.com/w/cpp/types/floating-point
+        Il2CppTypeEnum::R4 => "float32_t",
+        Il2CppTypeEnum::R8 => "float64_t",
+
+        Il2CppTypeEnum::Void => "void",
+        Il2CppTypeEnum::Boolean => "bool",
+        Il2CppTypeEnum::Char => "char16_t",
+        Il2CppTypeEnum::Ptr => "void*",
+        // il2cpp doesn't keep the signature of function pointers, only that it is one
+        Il2CppTypeEnum::Fnptr => "/* function pointer */ void*",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
 pub trait CSType: Sized {
     fn get_mut_cpp_type(&mut self) -> &mut CppType; // idk how else to do this
     fn get_cpp_type(&self) -> &CppType; // idk how else to do this
@@ -112,10 +251,15 @@ pub trait CSType: Sized {
             config.namespace_cpp(&full_name)
         };
 
+        let mut prefix_comments = vec![format!("Type: {ns}::{name}")];
+        if let Some(image_name) = metadata.tdi_to_image_name.get(&tdi) {
+            prefix_comments.push(format!("Assembly: {image_name}"));
+        }
+
         let mut cpptype = CppType {
             self_tag: tag,
             nested: parent_pair.is_some(),
-            prefix_comments: vec![format!("Type: {ns}::{name}")],
+            prefix_comments,
             namespace: config.namespace_cpp(ns),
             cpp_namespace: config.namespace_cpp(ns),
             name: config.name_cpp(name),
@@ -130,13 +274,39 @@ pub trait CSType: Sized {
             implementations: Default::default(),
             nonmember_implementations: Default::default(),
             nonmember_declarations: Default::default(),
+            global_declarations: Default::default(),
             is_value_type: t.is_value_type(),
+            instance_size: metadata
+                .metadata_registration
+                .type_definition_sizes
+                .as_ref()
+                .and_then(|sizes| sizes.get(tdi.index() as usize))
+                // Generic definitions are never instantiated, only their instantiations have a size
+                .filter(|_| !t.generic_container_index.is_valid())
+                // il2cpp counts the object header for value types too, their C++ struct has none
+                .map(|size| match t.is_value_type() {
+                    true => size.instance_size.saturating_sub(OBJECT_SIZE),
+                    false => size.instance_size,
+                }),
+            // Reference type fields are offset wrappers, packing only matters for value types
+            packing: t.packing().filter(|_| t.is_value_type()),
             requirements: Default::default(),
             inherit: Default::default(),
             generic_args: cpp_template,
             nested_types: Default::default(),
         };
 
+        // __CORDL_INSTANCE_SIZE
+        if cpptype.instance_size.is_some() {
+            cpptype
+                .requirements
+                .required_includes
+                .insert(CppInclude::new_system("cstddef".into()));
+        } else {
+            // Reported once, in the warning summary
+            record_warning("types without an instance size", format!("{ns}::{name}"));
+        }
+
         if t.parent_index == u32::MAX {
             if t.flags & TYPE_ATTRIBUTE_INTERFACE == 0 {
                 println!("Skipping type: {ns}::{name} because it has parent index: {} and is not an interface!", t.parent_index);
@@ -164,9 +334,63 @@ pub trait CSType: Sized {
         tdi: TypeDefinitionIndex,
     ) {
         self.make_parents(metadata, ctx_collection, tdi);
-        self.make_fields(metadata, ctx_collection, tdi);
-        self.make_properties(metadata, ctx_collection, tdi);
-        self.make_methods(metadata, config, ctx_collection, tdi);
+        self.make_fields(metadata, config, ctx_collection, tdi);
+        self.make_inherited_member_usings(metadata, config, ctx_collection, tdi);
+
+        // Layouts don't need any of the method machinery
+        if !config.layout_only {
+            // get_/set_ accessor methods are generated either way
+            if !config.no_property_wrappers {
+                self.make_properties(metadata, config, ctx_collection, tdi);
+            }
+            self.make_methods(metadata, config, ctx_collection, tdi);
+        }
+
+        if config.klass_accessor {
+            self.make_klass_accessor(config);
+        }
+
+        if Self::get_type_definition(metadata, tdi).is_enum_type() && !config.layout_only {
+            self.make_enum_boxing(config);
+        }
+
+        // Casts construct through the pointer ctor made alongside methods
+        let wraps_pointer = !config.layout_only
+            && Self::has_wrapping_ctor(
+                metadata,
+                config,
+                ctx_collection,
+                Self::get_type_definition(metadata, tdi),
+            );
+        if config.casts && wraps_pointer {
+            self.make_casts(config);
+        }
+
+        // As does null
+        if wraps_pointer {
+            self.make_null_sentinel();
+        }
+
+        if config.convertible_traits {
+            self.make_convertible_traits(config);
+        }
+
+        if config.comparisons {
+            self.make_comparison_operators(metadata, config, ctx_collection, tdi);
+        }
+
+        if config.abi_hash {
+            self.make_abi_hash();
+        }
+
+        let full_name =
+            Self::get_type_definition(metadata, tdi).full_name(metadata.metadata, false);
+        if let Some(includes) = config.extra_includes.get(&full_name) {
+            self.get_mut_cpp_type()
+                .requirements
+                .required_includes
+                .extend(includes.iter().cloned().map(CppInclude::new));
+        }
 
         if let Some(func) = metadata.custom_type_handler.get(&tdi) {
             func(self.get_mut_cpp_type())
@@ -212,7 +436,53 @@ pub trait CSType: Sized {
                     holder_cpp_ty_name: cpp_type.cpp_name().clone(),
                     parameters: fields,
                     is_constexpr: true,
+                    is_explicit: false,
+                    base_ctor: None,
                     template: CppTemplate::default(),
+                    il2cpp_utils: config.il2cpp_utils_namespace.clone(),
+                }));
+
+            // Value types are copied by value like in C#, memberwise over their fields.
+            // Not constexpr, defaulting makes them so when every field allows it
+            let self_ty = cpp_type.cpp_name().clone();
+            cpp_type
+                .declarations
+                .push(CppMember::Comment(CppCommentedString {
+                    data: "".to_string(),
+                    comment: Some("Copy and move".to_string()),
+                }));
+            for line in [
+                format!("{self_ty}({self_ty} const&) = default;"),
+                format!("{self_ty}({self_ty}&&) = default;"),
+                format!("{self_ty}& operator=({self_ty} const&) = default;"),
+                format!("{self_ty}& operator=({self_ty}&&) = default;"),
+            ] {
+                cpp_type
+                    .declarations
+                    .push(CppMember::CppLine(CppLine { line }));
+            }
+        } else if Self::has_wrapping_ctor(metadata, config, ctx_collection, t)
+            && let Some(base) = cpp_type.inherit.first()
+        {
+            // pointer ctor, wraps an existing instance
+            // The parent class is always first, interfaces after it are default constructed
+            let ptr_param = CppParam {
+                name: "ptr".to_string(),
+                ty: "void*".to_string(),
+                modifiers: "".to_string(),
+                def_value: None,
+            };
+            let base_ctor = Some((base.clone(), ptr_param.name.clone()));
+            cpp_type
+                .declarations
+                .push(CppMember::ConstructorImpl(CppConstructorImpl {
+                    holder_cpp_ty_name: cpp_type.cpp_name().clone(),
+                    parameters: vec![ptr_param],
+                    is_constexpr: false,
+                    is_explicit: true,
+                    base_ctor,
+                    template: CppTemplate::default(),
+                    il2cpp_utils: config.il2cpp_utils_namespace.clone(),
                 }));
         }
 
@@ -228,6 +498,9 @@ pub trait CSType: Sized {
 
             cpp_type.declarations.reserve(5 * t.method_count as usize);
 
+            // C# names and vtable slots of virtual methods, for the slot table
+            let mut slots: Vec<(&str, u16)> = vec![];
+
             // Then, for each method, write it out
             for (i, method) in t.methods(metadata.metadata).iter().enumerate() {
                 let method_index = MethodIndex::new(t.method_start.index() + i as u32);
@@ -241,6 +514,11 @@ pub trait CSType: Sized {
                     continue;
                 }
 
+                // Lambdas, iterator and async state methods etc.
+                if config.is_hidden_compiler_generated(m_name) {
+                    continue;
+                }
+
                 let m_ret_type = metadata
                     .metadata_registration
                     .types
@@ -248,6 +526,14 @@ pub trait CSType: Sized {
                     .unwrap();
                 let mut m_params: Vec<CppParam> =
                     Vec::with_capacity(method.parameter_count as usize);
+                // Parameters as the il2cpp ABI sees them, None when one can't be lowered
+                let mut abi_params: Option<Vec<(String, String)>> = Some(vec![]);
+                // Generated names must not shadow a real parameter later in the signature
+                let real_param_names = method
+                    .parameters(metadata.metadata)
+                    .iter()
+                    .map(|p| p.name(metadata.metadata))
+                    .collect_vec();
 
                 for (pi, param) in method.parameters(metadata.metadata).iter().enumerate() {
                     let param_index =
@@ -258,34 +544,74 @@ pub trait CSType: Sized {
                         .get(param.type_index as usize)
                         .unwrap();
 
-                    let param_cpp_name =
+                    let mut param_cpp_name =
                         cpp_type.cppify_name_il2cpp(ctx_collection, metadata, param_type, false);
 
-                    let def_value = Self::param_default_value(metadata, param_index);
+                    // ref/out, ByRef<T> converts implicitly from a plain T&
+                    if param_type.is_byref() {
+                        cpp_type.requirements.needs_byref_include();
+                        param_cpp_name = format!("::ByRef<{param_cpp_name}>");
+                    }
+
+                    let def_value =
+                        Self::param_default_value(metadata, param_index, &param_cpp_name);
+
+                    // Obfuscated or compiler generated metadata can have blank or repeated names
+                    let taken = m_params.iter().map(|p| p.name.as_str()).collect_vec();
+                    let p_name =
+                        param_name(param.name(metadata.metadata), pi, &real_param_names, &taken);
+
+                    // TODO: Lower ref/out parameters too
+                    let abi_param = match param_type.is_byref() {
+                        true => None,
+                        false if Self::is_reference_type(metadata, param_type) => {
+                            Some(("void*".to_string(), format!("{p_name}.convert()")))
+                        }
+                        false => Some((param_cpp_name.clone(), p_name.clone())),
+                    };
+                    abi_params = abi_params.zip(abi_param).map(|(mut params, param)| {
+                        params.push(param);
+                        params
+                    });
 
                     m_params.push(CppParam {
-                        name: param.name(metadata.metadata).to_string(),
+                        name: p_name,
                         def_value,
                         ty: param_cpp_name,
-                        modifiers: if param_type.is_byref() {
-                            String::from("byref")
-                        } else {
-                            String::from("")
-                        },
+                        modifiers: String::from(""),
                     });
                 }
 
-                let generics = if method.generic_container_index.is_valid() {
+                let generic_params = if method.generic_container_index.is_valid() {
                     method
                         .generic_container(metadata.metadata)
                         .unwrap()
                         .generic_parameters(metadata.metadata)
                         .iter()
-                        .map(|param| param.name(metadata.metadata).to_string())
                         .collect_vec()
                 } else {
                     vec![]
                 };
+                let generics = generic_params
+                    .iter()
+                    .map(|param| param.name(metadata.metadata).to_string())
+                    .collect_vec();
+
+                let generic_constraints = generic_params
+                    .iter()
+                    .filter_map(|param| {
+                        let types = param
+                            .constraints(metadata.metadata)
+                            .iter()
+                            .map(|&constraint| {
+                                let constraint_ty =
+                                    &metadata.metadata_registration.types[constraint as usize];
+                                Self::constraint_name(metadata, constraint_ty)
+                            })
+                            .collect_vec();
+                        generic_constraint(param.name(metadata.metadata), param.flags, types)
+                    })
+                    .collect_vec();
 
                 let template = CppTemplate { names: generics };
 
@@ -302,7 +628,10 @@ pub trait CSType: Sized {
                             holder_cpp_ty_name: cpp_type.cpp_name().clone(),
                             parameters: m_params.clone(),
                             is_constexpr: false,
+                            is_explicit: false,
+                            base_ctor: None,
                             template: template.clone(),
+                            il2cpp_utils: config.il2cpp_utils_namespace.clone(),
                         }));
                     cpp_type
                         .declarations
@@ -313,6 +642,11 @@ pub trait CSType: Sized {
                         }));
                 }
 
+                // ::std::uintptr_t rva
+                if !config.no_size_structs {
+                    cpp_type.requirements.needs_int_include();
+                }
+
                 let declaring_type = method.declaring_type(metadata.metadata);
                 let tag = TypeData::TypeDefinitionIndex(method.declaring_type);
                 let declaring_cpp_type: Option<&CppType> = if tag == cpp_type.self_tag {
@@ -321,29 +655,88 @@ pub trait CSType: Sized {
                     ctx_collection.get_cpp_type(tag)
                 };
 
-                cpp_type
-                    .nonmember_implementations
-                    .push(Rc::new(CppMethodSizeStruct {
-                        ret_ty: m_ret_cpp_type_name.clone(),
-                        cpp_method_name: config.name_cpp(m_name),
-                        complete_type_name: cpp_type.formatted_complete_cpp_name().clone(),
-                        instance: !method.is_static_method(),
-                        params: m_params.clone(),
-                        template: template.clone(),
-                        method_data: CppMethodData {
-                            addrs: method_calc.addrs,
-                            estimated_size: method_calc.estimated_size,
-                        },
-                        interface_clazz_of: declaring_cpp_type
-                            .map(|d| d.classof_cpp_name())
-                            .unwrap_or_else(|| format!("Bad stuff happened {declaring_type:?}")),
-                        is_final: method.is_final_method(),
-                        slot: if method.slot != u16::MAX {
-                            Some(method.slot)
-                        } else {
-                            None
-                        },
-                    }));
+                // Methods are invoked through the runtime, the C++ object itself is never mutated
+                let suffix_modifiers = match config.const_methods && !method.is_static_method() {
+                    true => "const".to_string(),
+                    false => "".to_string(),
+                };
+
+                let method_data = CppMethodData {
+                    addrs: method_calc.addrs,
+                    estimated_size: method_calc.estimated_size,
+                };
+                // Generic methods need their MethodInfo, so they always go through the runtime.
+                // Overridable virtual methods must be dispatched through the vtable
+                let direct_call = abi_params
+                    .filter(|_| {
+                        config.direct_calls
+                            && method_data.has_addrs()
+                            && (method.slot == u16::MAX
+                                || method.is_final_method()
+                                || t.is_sealed())
+                            && template.names.is_empty()
+                            && cpp_type.generic_args.names.is_empty()
+                    })
+                    .map(|params| {
+                        // il2cpp passes value type instances as a pointer to their data,
+                        // this may be const with --const-methods
+                        let instance_arg = match t.is_value_type() {
+                            true => "const_cast<void*>(static_cast<const void*>(this))",
+                            false => "this->convert()",
+                        };
+                        let return_ty = match m_ret_type.ty {
+                            Il2CppTypeEnum::Void => m_ret_cpp_type_name.clone(),
+                            _ if Self::is_reference_type(metadata, m_ret_type) => {
+                                "void*".to_string()
+                            }
+                            _ => m_ret_cpp_type_name.clone(),
+                        };
+
+                        CppDirectCall {
+                            addrs: method_data.addrs,
+                            instance_arg: instance_arg.to_string(),
+                            params,
+                            return_ty,
+                        }
+                    });
+
+                // Static methods have no this to look the method up on
+                let classof_call = cpp_type.classof_cpp_name(config);
+
+                if !config.no_size_structs {
+                    cpp_type
+                        .nonmember_implementations
+                        .push(Rc::new(CppMethodSizeStruct {
+                            ret_ty: m_ret_cpp_type_name.clone(),
+                            cpp_method_name: config.name_cpp(m_name),
+                            complete_type_name: cpp_type.formatted_complete_cpp_name().clone(),
+                            instance: !method.is_static_method(),
+                            params: m_params.clone(),
+                            template: template.clone(),
+                            method_data: CppMethodData {
+                                addrs: method_calc.addrs,
+                                estimated_size: method_calc.estimated_size,
+                            },
+                            interface_clazz_of: declaring_cpp_type
+                                .map(|d| d.classof_cpp_name(config))
+                                .unwrap_or_else(|| {
+                                    format!("Bad stuff happened {declaring_type:?}")
+                                }),
+                            is_final: method.is_final_method(),
+                            slot: if method.slot != u16::MAX {
+                                Some(method.slot)
+                            } else {
+                                None
+                            },
+                            suffix_modifiers: suffix_modifiers.clone(),
+                            il2cpp_utils: config.il2cpp_utils_namespace.clone(),
+                            classof_call: classof_call.clone(),
+                        }));
+                }
+                // getRealOffset
+                if direct_call.is_some() {
+                    cpp_type.requirements.needs_utils_functions_include();
+                }
                 cpp_type
                     .implementations
                     .push(CppMember::MethodImpl(CppMethodImpl {
@@ -357,10 +750,40 @@ pub trait CSType: Sized {
                         return_type: m_ret_cpp_type_name.clone(),
                         parameters: m_params.clone(),
                         instance: !method.is_static_method(),
-                        suffix_modifiers: Default::default(),
+                        has_size_struct: !config.no_size_structs,
+                        classof_call,
+                        direct_call,
+                        il2cpp_utils: config.il2cpp_utils_namespace.clone(),
+                        suffix_modifiers: suffix_modifiers.clone(),
                         prefix_modifiers: Default::default(),
                         template: template.clone(),
                     }));
+                // Explicit interface implementations are named Interface.Method,
+                // name_cpp turns that into a unique Interface_Method
+                if !m_name.starts_with('.')
+                    && let Some((interface, interface_method)) = m_name.rsplit_once('.')
+                {
+                    cpp_type
+                        .declarations
+                        .push(CppMember::Comment(CppCommentedString {
+                            data: "".to_string(),
+                            comment: Some(format!(
+                                "Explicit implementation of {interface_method} from {interface}"
+                            )),
+                        }));
+                }
+                // Not enforced, the arguments are only checked by the runtime
+                if !generic_constraints.is_empty() {
+                    cpp_type
+                        .declarations
+                        .push(CppMember::Comment(CppCommentedString {
+                            data: "".to_string(),
+                            comment: Some(format!(
+                                "Generic constraints: {}",
+                                generic_constraints.join("; ")
+                            )),
+                        }));
+                }
                 cpp_type
                     .declarations
                     .push(CppMember::MethodDecl(CppMethodDecl {
@@ -369,21 +792,760 @@ pub trait CSType: Sized {
                         parameters: m_params,
                         instance: !method.is_static_method(),
                         prefix_modifiers: Default::default(),
-                        suffix_modifiers: Default::default(),
+                        suffix_modifiers,
                         method_data: CppMethodData {
                             addrs: method_calc.addrs,
                             estimated_size: method_calc.estimated_size,
                         },
                         is_virtual: method.is_virtual_method() && !method.is_final_method(),
                         template,
+                        body: None,
                     }));
+
+                if method.slot != u16::MAX {
+                    slots.push((m_name, method.slot));
+                }
+            }
+
+            // The same slots the size structs hand to ResolveVtableSlot
+            if config.slot_tables && !slots.is_empty() {
+                for include in ["array", "cstdint", "string_view", "utility"] {
+                    cpp_type
+                        .requirements
+                        .required_includes
+                        .insert(CppInclude::new_system(include.into()));
+                }
+
+                let entries = slots
+                    .iter()
+                    .map(|(name, slot)| format!("  {{\"{name}\", {slot}}},"))
+                    .join("\n");
+
+                cpp_type
+                    .declarations
+                    .push(CppMember::Comment(CppCommentedString {
+                        data: "".to_string(),
+                        comment: Some("Virtual method names and vtable slots".to_string()),
+                    }));
+                cpp_type
+                    .declarations
+                    .push(CppMember::CppLine(CppLine {
+                        line: format!(
+                            "static constexpr ::std::array<::std::pair<::std::string_view, ::std::uint16_t>, {}> __CORDL_SLOT_TABLE{{{{\n{entries}\n}}}};",
+                            slots.len()
+                        ),
+                    }));
+            }
+        }
+
+        // Declaring any overload of a System.Object method hides the inherited ones in C++,
+        // so bring them back in for e.g. obj.Equals(other) on a type with Equals(T)
+        if t.parent_index != u32::MAX
+            && let Some(base) = cpp_type.inherit.first().cloned()
+        {
+            for object_method in ["Equals", "GetHashCode", "ToString", "GetType"] {
+                let overloads = t
+                    .methods(metadata.metadata)
+                    .iter()
+                    .filter(|m| m.name(metadata.metadata) == object_method)
+                    .collect_vec();
+                if overloads.is_empty() {
+                    continue;
+                }
+
+                if Self::base_declares_method(metadata, config, ctx_collection, t, object_method) {
+                    cpp_type
+                        .declarations
+                        .push(CppMember::CppLine(CppLine {
+                            line: format!("using {base}::{object_method};"),
+                        }));
+                    continue;
+                }
+
+                // The System.Object wrapper has none of its methods to bring back,
+                // so forward them to the runtime instead
+                let (return_type, parameters) = match object_method {
+                    "Equals" => {
+                        cpp_type.requirements.need_wrapper();
+                        let obj = CppParam {
+                            name: "obj".to_string(),
+                            ty: "::bs_hook::Il2CppWrapperType".to_string(),
+                            modifiers: "".to_string(),
+                            def_value: None,
+                        };
+                        ("bool".to_string(), vec![obj])
+                    }
+                    "GetHashCode" => {
+                        cpp_type.requirements.needs_int_include();
+                        ("int32_t".to_string(), vec![])
+                    }
+                    "ToString" => {
+                        cpp_type.requirements.needs_stringw_include();
+                        ("::StringW".to_string(), vec![])
+                    }
+                    // TODO: System.Type isn't necessarily known to this type
+                    _ => continue,
+                };
+
+                // Already generated when this type overrides it
+                let declared = overloads.iter().any(|m| {
+                    m.parameter_count as usize == parameters.len()
+                        && m.parameters(metadata.metadata).iter().all(|p| {
+                            metadata.metadata_registration.types[p.type_index as usize].ty
+                                == Il2CppTypeEnum::Object
+                        })
+                });
+                if declared {
+                    continue;
+                }
+
+                let il2cpp_utils = &config.il2cpp_utils_namespace;
+                let instance_arg = match config.const_methods {
+                    true => format!("const_cast<{}*>(this)", cpp_type.cpp_name()),
+                    false => "this".to_string(),
+                };
+                let arg_types = CppParam::params_il2cpp_types(&parameters, il2cpp_utils);
+                let args = parameters
+                    .iter()
+                    .map(|p| format!(", {}", p.name))
+                    .collect::<String>();
+                let mut forward = CppMethodDecl::new_inline(
+                    object_method.to_string(),
+                    return_type.clone(),
+                    parameters,
+                    true,
+                    vec![
+                        format!(
+                            "static auto ___internal__method = THROW_UNLESS({il2cpp_utils}::FindMethod({instance_arg}, \"{object_method}\", std::vector<Il2CppClass*>{{}}, ::std::vector<const Il2CppType*>{{{arg_types}}}));"
+                        ),
+                        format!(
+                            "return {il2cpp_utils}::RunMethodRethrow<{return_type}, false>({instance_arg}, ___internal__method{args});"
+                        ),
+                    ],
+                );
+                if config.const_methods {
+                    forward.suffix_modifiers = "const".to_string();
+                }
+                cpp_type
+                    .declarations
+                    .push(CppMember::MethodDecl(forward));
+            }
+        }
+    }
+
+    /// Whether the explicit pointer ctor wrapping an existing instance is generated.
+    /// Interfaces are only bases, and must stay default constructible.
+    /// It forwards to the parent's, which C++ only allows for the direct base,
+    /// so every ancestor up to System.Object needs one as well
+    fn has_wrapping_ctor(
+        metadata: &Metadata,
+        config: &GenerationConfig,
+        ctx_collection: &CppContextCollection,
+        t: &Il2CppTypeDefinition,
+    ) -> bool {
+        if t.is_value_type()
+            || t.flags & TYPE_ATTRIBUTE_INTERFACE != 0
+            || t.parent_index == u32::MAX
+            || Self::has_pointer_ctor(metadata, t)
+        {
+            return false;
+        }
+
+        let parent_ty = &metadata.metadata_registration.types[t.parent_index as usize];
+        let Some(parent_tdi) = Self::definition_index(metadata, parent_ty) else {
+            return false;
+        };
+        let parent = &metadata.metadata.global_metadata.type_definitions[parent_tdi];
+
+        // System.Object is represented by ::bs_hook::Il2CppWrapperType, which wraps pointers
+        if parent.parent_index == u32::MAX {
+            return true;
+        }
+
+        // Excluded types are never filled, so they have no ctors
+        if Self::is_excluded(metadata, config, ctx_collection, parent_tdi) {
+            return false;
+        }
+
+        Self::has_wrapping_ctor(metadata, config, ctx_collection, parent)
+    }
+
+    /// Type definition of a type or of the generic type it instantiates
+    fn definition_index(metadata: &Metadata, ty: &Il2CppType) -> Option<TypeDefinitionIndex> {
+        match ty.data {
+            TypeData::TypeDefinitionIndex(tdi) => Some(tdi),
+            TypeData::GenericClassIndex(e) => {
+                let mr = &metadata.metadata_registration;
+                match mr.types[mr.generic_classes[e].type_index].data {
+                    TypeData::TypeDefinitionIndex(tdi) => Some(tdi),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the type is in an excluded namespace, or nested in a type that is
+    fn is_excluded(
+        metadata: &Metadata,
+        config: &GenerationConfig,
+        ctx_collection: &CppContextCollection,
+        tdi: TypeDefinitionIndex,
+    ) -> bool {
+        let root_tag = ctx_collection.get_context_root_tag(TypeData::TypeDefinitionIndex(tdi));
+        let root = &metadata.metadata.global_metadata.type_definitions[Self::get_tag_tdi(root_tag)];
+        config.is_namespace_excluded(root.namespace(metadata.metadata))
+    }
+
+    /// Whether values of the type are object references, passed to il2cpp as a pointer
+    fn is_reference_type(metadata: &Metadata, ty: &Il2CppType) -> bool {
+        match ty.ty {
+            Il2CppTypeEnum::Class
+            | Il2CppTypeEnum::String
+            | Il2CppTypeEnum::Object
+            | Il2CppTypeEnum::Szarray
+            | Il2CppTypeEnum::Array => true,
+            Il2CppTypeEnum::Genericinst => match ty.data {
+                TypeData::GenericClassIndex(e) => {
+                    let mr = &metadata.metadata_registration;
+                    let generic_class = &mr.generic_classes[e];
+                    Self::is_reference_type(metadata, &mr.types[generic_class.type_index])
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// C# name of a generic constraint type, only read from metadata so that
+    /// naming it in a comment doesn't pull in includes or forward declares
+    fn constraint_name(metadata: &Metadata, ty: &Il2CppType) -> String {
+        let global_metadata = &metadata.metadata.global_metadata;
+        match ty.data {
+            TypeData::TypeDefinitionIndex(tdi) => {
+                global_metadata.type_definitions[tdi].full_name(metadata.metadata, false)
+            }
+            TypeData::GenericParameterIndex(index) => global_metadata.generic_parameters[index]
+                .name(metadata.metadata)
+                .to_string(),
+            TypeData::GenericClassIndex(e) => {
+                let mr = &metadata.metadata_registration;
+                let generic_class = &mr.generic_classes[e];
+                let owner_name =
+                    Self::constraint_name(metadata, &mr.types[generic_class.type_index]);
+                let Some(inst_idx) = generic_class.context.class_inst_idx else {
+                    return owner_name;
+                };
+                let args = mr.generic_insts[inst_idx]
+                    .types
+                    .iter()
+                    .map(|&t| Self::constraint_name(metadata, &mr.types[t]))
+                    .join(", ");
+
+                format!("{owner_name}<{args}>")
+            }
+            _ => format!("{:?}", ty.ty),
+        }
+    }
+
+    /// A C# .ctor taking a single pointer, which would collide with the wrapping pointer ctor
+    fn has_pointer_ctor(metadata: &Metadata, t: &Il2CppTypeDefinition) -> bool {
+        t.methods(metadata.metadata).iter().any(|m| {
+            m.name(metadata.metadata) == ".ctor"
+                && m.parameter_count == 1
+                && m.parameters(metadata.metadata).iter().all(|p| {
+                    let ty = &metadata.metadata_registration.types[p.type_index as usize];
+                    matches!(ty.ty, Il2CppTypeEnum::Ptr | Il2CppTypeEnum::Fnptr) && !ty.is_byref()
+                })
+        })
+    }
+
+    /// A field or property reachable through several bases, e.g. a parent class and an
+    /// interface both with Count, is ambiguous in C++ unless this type redeclares it.
+    /// C# resolves those to the class member, so prefer the parent, else the first interface
+    fn make_inherited_member_usings(
+        &mut self,
+        metadata: &Metadata,
+        config: &GenerationConfig,
+        ctx_collection: &CppContextCollection,
+        tdi: TypeDefinitionIndex,
+    ) {
+        let cpp_type = self.get_mut_cpp_type();
+        let t = Self::get_type_definition(metadata, tdi);
+
+        // Same order as make_parents
+        let base_types = (t.parent_index != u32::MAX)
+            .then_some(t.parent_index)
+            .into_iter()
+            .chain(t.interfaces(metadata.metadata).iter().copied())
+            .map(|index| &metadata.metadata_registration.types[index as usize]);
+
+        let mut providers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (base_ty, base) in base_types.zip(cpp_type.inherit.iter()) {
+            for name in Self::inherited_member_names(metadata, config, ctx_collection, base_ty) {
+                providers.entry(name).or_default().push(base.clone());
+            }
+        }
+
+        let own_names = Self::declared_member_names(metadata, config, t, true);
+        for (name, bases) in providers {
+            if bases.len() < 2 || own_names.contains(&name) {
+                continue;
             }
+
+            cpp_type
+                .declarations
+                .push(CppMember::Comment(CppCommentedString {
+                    data: "".to_string(),
+                    comment: Some(format!("{name} is inherited from {}", bases.join(", "))),
+                }));
+            cpp_type.declarations.push(CppMember::CppLine(CppLine {
+                line: format!("using {}::{name};", bases[0]),
+            }));
         }
     }
 
+    /// Names of the fields and properties generated on a type, and optionally its methods
+    fn declared_member_names(
+        metadata: &Metadata,
+        config: &GenerationConfig,
+        t: &Il2CppTypeDefinition,
+        with_methods: bool,
+    ) -> BTreeSet<String> {
+        let fields = t
+            .fields(metadata.metadata)
+            .iter()
+            .map(|f| f.name(metadata.metadata))
+            .filter(|name| !config.is_hidden_compiler_generated(name));
+        let properties = t
+            .properties(metadata.metadata)
+            .iter()
+            .filter(|_| !config.layout_only && !config.no_property_wrappers)
+            .map(|p| p.name(metadata.metadata));
+        let methods = t
+            .methods(metadata.metadata)
+            .iter()
+            .filter(|_| with_methods)
+            .map(|m| m.name(metadata.metadata));
+
+        fields
+            .chain(properties)
+            .chain(methods)
+            // Explicit implementations and compiler generated names aren't identifiers
+            .filter(|name| !name.contains(['.', '<']))
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    /// Field and property names generated on a base type and everything it inherits
+    fn inherited_member_names(
+        metadata: &Metadata,
+        config: &GenerationConfig,
+        ctx_collection: &CppContextCollection,
+        ty: &Il2CppType,
+    ) -> BTreeSet<String> {
+        let Some(tdi) = Self::definition_index(metadata, ty) else {
+            return BTreeSet::new();
+        };
+        let t = &metadata.metadata.global_metadata.type_definitions[tdi];
+
+        // System.Object is represented by ::bs_hook::Il2CppWrapperType
+        if t.parent_index == u32::MAX && t.flags & TYPE_ATTRIBUTE_INTERFACE == 0 {
+            return BTreeSet::new();
+        }
+
+        // Excluded types are never filled, so they have no members
+        if Self::is_excluded(metadata, config, ctx_collection, tdi) {
+            return BTreeSet::new();
+        }
+
+        let mut names = Self::declared_member_names(metadata, config, t, false);
+        let bases = (t.parent_index != u32::MAX)
+            .then_some(t.parent_index)
+            .into_iter()
+            .chain(t.interfaces(metadata.metadata).iter().copied());
+        for index in bases {
+            let base_ty = &metadata.metadata_registration.types[index as usize];
+            names.extend(Self::inherited_member_names(
+                metadata,
+                config,
+                ctx_collection,
+                base_ty,
+            ));
+        }
+
+        names
+    }
+
+    /// Whether a generated base type, rather than the System.Object wrapper, has the method
+    fn base_declares_method(
+        metadata: &Metadata,
+        config: &GenerationConfig,
+        ctx_collection: &CppContextCollection,
+        t: &Il2CppTypeDefinition,
+        method_name: &str,
+    ) -> bool {
+        let mut parent_index = t.parent_index;
+        while parent_index != u32::MAX {
+            let parent_ty = &metadata.metadata_registration.types[parent_index as usize];
+            // TODO: Look through generic instantiations too
+            let TypeData::TypeDefinitionIndex(parent_tdi) = parent_ty.data else {
+                return false;
+            };
+            let parent = &metadata.metadata.global_metadata.type_definitions[parent_tdi];
+
+            // System.Object is represented by ::bs_hook::Il2CppWrapperType
+            if parent.parent_index == u32::MAX {
+                return false;
+            }
+
+            // Excluded types are never filled, so they have no methods
+            if Self::is_excluded(metadata, config, ctx_collection, parent_tdi) {
+                return false;
+            }
+
+            if parent
+                .methods(metadata.metadata)
+                .iter()
+                .any(|m| m.name(metadata.metadata) == method_name)
+            {
+                return true;
+            }
+
+            parent_index = parent.parent_index;
+        }
+
+        false
+    }
+
+    fn make_klass_accessor(&mut self, config: &GenerationConfig) {
+        let classof_call = self.classof_cpp_name(config);
+        let cpp_type = self.get_mut_cpp_type();
+
+        cpp_type
+            .declarations
+            .push(CppMember::Comment(CppCommentedString {
+                data: "".to_string(),
+                comment: Some("Runtime class of this type".to_string()),
+            }));
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(CppMethodDecl::new_inline(
+                "klass".to_string(),
+                "Il2CppClass*".to_string(),
+                vec![],
+                false,
+                vec![format!("return {classof_call}();")],
+            )));
+    }
+
+    fn make_enum_boxing(&mut self, config: &GenerationConfig) {
+        let classof_call = self.classof_cpp_name(config);
+        let il2cpp_functions = &config.il2cpp_functions_namespace;
+        let cpp_type = self.get_mut_cpp_type();
+        let self_ty = cpp_type.cpp_name().clone();
+
+        // The enum's class knows its backing type, so boxing the whole struct is enough
+        cpp_type
+            .declarations
+            .push(CppMember::Comment(CppCommentedString {
+                data: "".to_string(),
+                comment: Some("Boxing to and from object".to_string()),
+            }));
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(CppMethodDecl::new_inline(
+                "box".to_string(),
+                "Il2CppObject*".to_string(),
+                vec![],
+                true,
+                vec![format!(
+                    "return {il2cpp_functions}::value_box({classof_call}(), this);"
+                )],
+            )));
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(CppMethodDecl::new_inline(
+                "unbox".to_string(),
+                self_ty.clone(),
+                vec![CppParam {
+                    name: "obj".to_string(),
+                    ty: "Il2CppObject*".to_string(),
+                    modifiers: "".to_string(),
+                    def_value: None,
+                }],
+                false,
+                vec![format!(
+                    "return *static_cast<{self_ty}*>({il2cpp_functions}::object_unbox(obj));"
+                )],
+            )));
+    }
+
+    fn make_casts(&mut self, config: &GenerationConfig) {
+        let classof_call = self.classof_cpp_name(config);
+        let il2cpp_functions = &config.il2cpp_functions_namespace;
+        let cpp_type = self.get_mut_cpp_type();
+
+        // Value types can't be downcast to, and types without a base have no pointer ctor
+        if cpp_type.is_value_type || cpp_type.inherit.is_empty() {
+            return;
+        }
+
+        let self_ty = cpp_type.cpp_name().clone();
+        cpp_type
+            .requirements
+            .required_includes
+            .insert(CppInclude::new_system("optional".into()));
+        cpp_type
+            .declarations
+            .push(CppMember::Comment(CppCommentedString {
+                data: "".to_string(),
+                comment: Some("C# `as`, empty when other is null or not an instance".to_string()),
+            }));
+        let mut try_cast = CppMethodDecl::new_inline(
+            "try_cast".to_string(),
+            format!("::std::optional<{self_ty}>"),
+            vec![CppParam {
+                name: "other".to_string(),
+                ty: "T".to_string(),
+                modifiers: "".to_string(),
+                def_value: None,
+            }],
+            false,
+            vec![
+                "auto* obj = static_cast<Il2CppObject*>(other.convert());".to_string(),
+                format!(
+                    "if (obj && {il2cpp_functions}::class_is_assignable_from({classof_call}(), obj->klass)) {{"
+                ),
+                format!("  return {self_ty}(obj);"),
+                "}".to_string(),
+                "return ::std::nullopt;".to_string(),
+            ],
+        );
+        try_cast.template = CppTemplate {
+            names: vec!["T".to_string()],
+        };
+        cpp_type.declarations.push(CppMember::MethodDecl(try_cast));
+    }
+
+    fn make_null_sentinel(&mut self) {
+        let cpp_type = self.get_mut_cpp_type();
+
+        // Value types can't be null, and types without a base have no pointer ctor
+        if cpp_type.is_value_type || cpp_type.inherit.is_empty() {
+            return;
+        }
+
+        let self_ty = cpp_type.cpp_name().clone();
+        cpp_type
+            .declarations
+            .push(CppMember::Comment(CppCommentedString {
+                data: "".to_string(),
+                comment: Some("Typed null, for overloads where nullptr is ambiguous".to_string()),
+            }));
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(CppMethodDecl::new_inline(
+                "null".to_string(),
+                self_ty.clone(),
+                vec![],
+                false,
+                vec![format!("return {self_ty}(nullptr);")],
+            )));
+    }
+
+    fn make_comparison_operators(
+        &mut self,
+        metadata: &Metadata,
+        config: &GenerationConfig,
+        ctx_collection: &CppContextCollection,
+        tdi: TypeDefinitionIndex,
+    ) {
+        let t = Self::get_type_definition(metadata, tdi);
+        let cpp_type = self.get_mut_cpp_type();
+
+        // IComparable declares int CompareTo(object), IComparable<T> int CompareTo(T)
+        let mut comparands = vec![];
+        for &interface_index in t.interfaces(metadata.metadata) {
+            let int_ty = &metadata.metadata_registration.types[interface_index as usize];
+            let Some(int_tdi) = Self::definition_index(metadata, int_ty) else {
+                continue;
+            };
+            let int_td = &metadata.metadata.global_metadata.type_definitions[int_tdi];
+            if int_td.namespace(metadata.metadata) != "System" {
+                continue;
+            }
+
+            let comparand = match (int_td.name(metadata.metadata), int_ty.data) {
+                ("IComparable", _) => "::bs_hook::Il2CppWrapperType".to_string(),
+                ("IComparable`1", TypeData::GenericClassIndex(e)) => {
+                    let mr = &metadata.metadata_registration;
+                    let Some(inst_idx) = mr.generic_classes[e].context.class_inst_idx else {
+                        continue;
+                    };
+                    let arg = &mr.types[mr.generic_insts[inst_idx].types[0]];
+                    // Already required by CompareTo itself
+                    cpp_type.cppify_name_il2cpp(ctx_collection, metadata, arg, false)
+                }
+                _ => continue,
+            };
+            comparands.push(comparand);
+        }
+
+        // Explicit implementations have a different name, and can't be called as CompareTo
+        let comparands = comparands
+            .into_iter()
+            .unique()
+            .filter(|comparand| {
+                cpp_type.declarations.iter().any(|d| match d {
+                    CppMember::MethodDecl(m) => {
+                        m.cpp_name == "CompareTo"
+                            && m.instance
+                            && m.body.is_none()
+                            && m.parameters.len() == 1
+                            && &m.parameters[0].ty == comparand
+                    }
+                    _ => false,
+                })
+            })
+            .collect_vec();
+
+        // Only const when CompareTo can be called on a const object
+        for comparand in comparands {
+            cpp_type
+                .declarations
+                .push(CppMember::Comment(CppCommentedString {
+                    data: "".to_string(),
+                    comment: Some(format!("Ordering through CompareTo({comparand})")),
+                }));
+
+            for op in ["<", "<=", ">", ">="] {
+                let mut operator = CppMethodDecl::new_inline(
+                    format!("operator{op}"),
+                    "bool".to_string(),
+                    vec![CppParam {
+                        name: "other".to_string(),
+                        ty: comparand.clone(),
+                        modifiers: "".to_string(),
+                        def_value: None,
+                    }],
+                    true,
+                    vec![format!("return this->CompareTo(other) {op} 0;")],
+                );
+                if config.const_methods {
+                    operator.suffix_modifiers = "const".to_string();
+                }
+                cpp_type.declarations.push(CppMember::MethodDecl(operator));
+            }
+        }
+    }
+
+    fn make_abi_hash(&mut self) {
+        let cpp_type = self.get_mut_cpp_type();
+
+        // FNV-1a, stable across runs and compiler versions unlike std's hasher
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut feed = |s: &str| {
+            for b in s.bytes().chain(std::iter::once(0)) {
+                hash ^= b as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        };
+
+        feed(&cpp_type.instance_size.unwrap_or_default().to_string());
+        cpp_type.inherit.iter().for_each(|i| feed(i));
+        for member in &cpp_type.declarations {
+            match member {
+                CppMember::Field(f) => {
+                    feed(&f.name);
+                    feed(&f.ty);
+                    feed(&f.offset.to_string());
+                    feed(&f.instance.to_string());
+                }
+                // Inline helpers depend on the generation flags, not on the binary
+                CppMember::MethodDecl(m) if m.body.is_none() => {
+                    feed(&m.cpp_name);
+                    feed(&m.return_type);
+                    m.parameters.iter().for_each(|p| feed(&p.ty));
+                    feed(&m.instance.to_string());
+                }
+                _ => {}
+            }
+        }
+
+        cpp_type.requirements.needs_int_include();
+        cpp_type
+            .declarations
+            .push(CppMember::Comment(CppCommentedString {
+                data: "".to_string(),
+                comment: Some("ABI change detection only, this is not a secure hash".to_string()),
+            }));
+        cpp_type.declarations.push(CppMember::CppLine(CppLine {
+            line: format!("static constexpr ::std::uint64_t __CORDL_ABI_HASH = 0x{hash:x};"),
+        }));
+    }
+
+    fn make_convertible_traits(&mut self, config: &GenerationConfig) {
+        let cpp_type = self.get_mut_cpp_type();
+
+        // Specializations can't be written inside of the enclosing type
+        if cpp_type.nested || cpp_type.inherit.is_empty() {
+            return;
+        }
+
+        cpp_type
+            .requirements
+            .required_includes
+            .insert(CppInclude::new_generated(
+                config.convertible_traits_path(),
+                config.include_root(),
+            ));
+
+        let template = &cpp_type.generic_args.names;
+        let (template_decl, self_ty) = match template.is_empty() {
+            true => (
+                "template<>".to_string(),
+                cpp_type.formatted_complete_cpp_name().clone(),
+            ),
+            false => (
+                format!(
+                    "template<{}>",
+                    template.iter().map(|s| format!("typename {s}")).join(",")
+                ),
+                format!(
+                    "{}<{}>",
+                    cpp_type.formatted_complete_cpp_name(),
+                    template.join(",")
+                ),
+            ),
+        };
+
+        let specializations = cpp_type
+            .inherit
+            .iter()
+            .map(|base| {
+                format!(
+                    "{template_decl}\nstruct ::cordl::is_convertible<{self_ty}, {base}> : ::std::true_type {{}};"
+                )
+            })
+            .collect_vec();
+
+        // Specializing ::cordl templates must happen outside of the type's namespace
+        cpp_type
+            .global_declarations
+            .push(Rc::new(CppCommentedString {
+                data: "".to_string(),
+                comment: Some("Base types and interfaces of this type".to_string()),
+            }));
+        cpp_type.global_declarations.push(Rc::new(CppLine {
+            line: specializations.join("\n"),
+        }));
+    }
+
     fn make_fields(
         &mut self,
         metadata: &Metadata,
+        config: &GenerationConfig,
         ctx_collection: &CppContextCollection,
         tdi: TypeDefinitionIndex,
     ) {
@@ -403,14 +1565,27 @@ pub trait CSType: Sized {
             }));
         // Then, for each field, write it out
         cpp_type.declarations.reserve(t.field_count as usize);
+        let mut instance_offsets: Vec<(String, u32)> = vec![];
         for (i, field) in t.fields(metadata.metadata).iter().enumerate() {
             let field_index = FieldIndex::new(t.field_start.index() + i as u32);
             let f_name = field.name(metadata.metadata);
-            let f_offset = metadata
+            let mut f_offset = metadata
                 .metadata_registration
                 .field_offsets
                 .as_ref()
                 .unwrap()[tdi.index() as usize][i];
+            // User supplied offsets win, e.g. for hotfixed binaries
+            if let Some(&offset) = config
+                .offset_overrides
+                .get(&t.full_name(metadata.metadata, false))
+                .and_then(|fields| fields.get(f_name))
+            {
+                println!(
+                    "Overriding offset of {}.{f_name}: 0x{f_offset:x} -> 0x{offset:x}",
+                    t.full_name(metadata.metadata, false)
+                );
+                f_offset = offset;
+            }
             let f_type = metadata
                 .metadata_registration
                 .types
@@ -419,22 +1594,134 @@ pub trait CSType: Sized {
 
             let _f_type_data = f_type.data;
 
-            let cpp_name = cpp_type.cppify_name_il2cpp(ctx_collection, metadata, f_type, false);
+            // Constants are defined inline, so value types such as enums
+            // from other contexts must be complete rather than forward declared
+            let needs_definition =
+                f_type.is_const() && matches!(f_type.ty, Il2CppTypeEnum::Valuetype);
+
+            let cpp_name =
+                cpp_type.cppify_name_il2cpp(ctx_collection, metadata, f_type, needs_definition);
+
+            let def_value = Self::field_default_value(metadata, field_index, &cpp_name);
+
+            let cs_modifiers = [
+                Some(f_type.field_access()),
+                (f_type.is_static() && !f_type.is_const()).then_some("static"),
+                f_type.is_const().then_some("const"),
+                f_type.is_init_only().then_some("readonly"),
+            ]
+            .into_iter()
+            .flatten()
+            .join(" ");
+
+            let instance = !f_type.is_static() && !f_type.is_const();
+
+            // Reference type fields can't overlap the object header, the offset was misread.
+            // Generic definitions have no layout, their offsets are 0 as in unknown
+            if instance
+                && !t.is_value_type()
+                && !t.generic_container_index.is_valid()
+                && f_offset < OBJECT_SIZE
+            {
+                println!(
+                    "Warning! Skipping field {f_name} of {}, its offset 0x{f_offset:x} is inside the object header",
+                    t.full_name(metadata.metadata, false)
+                );
+                record_warning(
+                    "fields inside the object header",
+                    t.full_name(metadata.metadata, false),
+                );
+                continue;
+            }
+
+            if instance {
+                // value type offsets are relative to the boxed object
+                let instance_offset = match t.is_value_type() {
+                    true => f_offset.saturating_sub(OBJECT_SIZE),
+                    false => f_offset,
+                };
+                instance_offsets.push((f_name.to_owned(), instance_offset));
+            }
 
-            let def_value = Self::field_default_value(metadata, field_index);
+            // Backing fields are accessed through their property instead.
+            // Value type fields are kept as they make up the struct layout
+            if config.is_hidden_compiler_generated(f_name) && (!instance || !t.is_value_type()) {
+                continue;
+            }
 
             // Need to include this type
             cpp_type.declarations.push(CppMember::Field(CppField {
                 name: f_name.to_owned(),
                 ty: cpp_name,
                 offset: f_offset,
-                instance: !f_type.is_static() && !f_type.is_const(),
-                readonly: f_type.is_const(),
-                classof_call: cpp_type.classof_cpp_name(),
+                instance,
+                // readonly fields are only assigned by constructors, don't expose a setter
+                readonly: f_type.is_readonly(),
+                classof_call: cpp_type.classof_cpp_name(config),
                 literal_value: def_value,
                 use_wrapper: !t.is_value_type(),
+                cs_modifiers,
             }));
         }
+
+        // Generic definitions have no layout, their offsets are all unknown
+        if config.field_offsets
+            && !t.generic_container_index.is_valid()
+            && !instance_offsets.is_empty()
+        {
+            cpp_type
+                .requirements
+                .required_includes
+                .insert(CppInclude::new_system("cstddef".into()));
+
+            cpp_type
+                .declarations
+                .push(CppMember::Comment(CppCommentedString {
+                    data: "".to_string(),
+                    comment: Some("Instance field offsets".to_string()),
+                }));
+            for (name, offset) in &instance_offsets {
+                cpp_type.declarations.push(CppMember::CppLine(CppLine {
+                    line: format!(
+                        "static constexpr ::std::size_t {}_offset = 0x{offset:x};",
+                        config.name_cpp(name)
+                    ),
+                }));
+            }
+        }
+
+        // Generic definitions have no layout, their offsets are all unknown
+        if config.field_tables
+            && !t.generic_container_index.is_valid()
+            && !instance_offsets.is_empty()
+        {
+            for include in ["array", "cstddef", "string_view", "utility"] {
+                cpp_type
+                    .requirements
+                    .required_includes
+                    .insert(CppInclude::new_system(include.into()));
+            }
+
+            let entries = instance_offsets
+                .iter()
+                .map(|(name, offset)| format!("  {{\"{name}\", 0x{offset:x}}},"))
+                .join("\n");
+
+            cpp_type
+                .declarations
+                .push(CppMember::Comment(CppCommentedString {
+                    data: "".to_string(),
+                    comment: Some("Instance field names and offsets".to_string()),
+                }));
+            cpp_type
+                .declarations
+                .push(CppMember::CppLine(CppLine {
+                    line: format!(
+                        "static constexpr ::std::array<::std::pair<::std::string_view, ::std::size_t>, {}> __CORDL_FIELD_TABLE{{{{\n{entries}\n}}}};",
+                        instance_offsets.len()
+                    ),
+                }));
+        }
     }
 
     fn make_parents(
@@ -513,6 +1800,7 @@ pub trait CSType: Sized {
     fn make_properties(
         &mut self,
         metadata: &Metadata,
+        config: &GenerationConfig,
         ctx_collection: &CppContextCollection,
         tdi: TypeDefinitionIndex,
     ) {
@@ -562,7 +1850,7 @@ pub trait CSType: Sized {
             cpp_type.declarations.push(CppMember::Property(CppProperty {
                 name: p_name.to_owned(),
                 ty: p_cpp_name.clone(),
-                classof_call: cpp_type.classof_cpp_name(),
+                classof_call: cpp_type.classof_cpp_name(config),
                 setter: p_setter.map(|_| method_map(prop.set_method_index(t))),
                 getter: p_getter.map(|_| method_map(prop.get_method_index(t))),
                 abstr: p_getter.is_some_and(|p| p.is_abstract_method())
@@ -590,14 +1878,14 @@ pub trait CSType: Sized {
             }
             // TODO: We assume 64 bit
             Il2CppTypeEnum::I | Il2CppTypeEnum::I8 => {
-                cursor.read_i64::<Endian>().unwrap().to_string()
+                int64_literal(cursor.read_i64::<Endian>().unwrap())
             }
             Il2CppTypeEnum::U1 => cursor.read_u8().unwrap().to_string(),
             Il2CppTypeEnum::U2 => cursor.read_u16::<Endian>().unwrap().to_string(),
             Il2CppTypeEnum::U4 => cursor.read_u32::<Endian>().unwrap().to_string(),
             // TODO: We assume 64 bit
             Il2CppTypeEnum::U | Il2CppTypeEnum::U8 => {
-                cursor.read_u64::<Endian>().unwrap().to_string()
+                uint64_literal(cursor.read_u64::<Endian>().unwrap())
             }
 
             // https://learn.microsoft.com/en-us/nimbusml/concepts/types
@@ -624,7 +1912,43 @@ pub trait CSType: Sized {
         }
     }
 
-    fn field_default_value(metadata: &Metadata, field_index: FieldIndex) -> Option<String> {
+    /// Underlying integer type of an enum, whose default values are stored as it
+    fn enum_backing_type<'a>(metadata: &'a Metadata, ty: &Il2CppType) -> Option<&'a Il2CppType> {
+        let TypeData::TypeDefinitionIndex(tdi) = ty.data else {
+            return None;
+        };
+        let type_def = &metadata.metadata.global_metadata.type_definitions[tdi];
+
+        match ty.ty == Il2CppTypeEnum::Valuetype && type_def.is_enum_type() {
+            true => metadata
+                .metadata_registration
+                .types
+                .get(type_def.element_type_index as usize),
+            false => None,
+        }
+    }
+
+    /// Reads a default value, constructing enums from their backing value
+    fn typed_default_value(
+        metadata: &Metadata,
+        ty: &Il2CppType,
+        cpp_name: &str,
+        data_index: usize,
+    ) -> String {
+        match Self::enum_backing_type(metadata, ty) {
+            Some(backing) => enum_default_value(
+                cpp_name,
+                &Self::default_value_blob(metadata, backing.ty, data_index),
+            ),
+            None => Self::default_value_blob(metadata, ty.ty, data_index),
+        }
+    }
+
+    fn field_default_value(
+        metadata: &Metadata,
+        field_index: FieldIndex,
+        cpp_name: &str,
+    ) -> Option<String> {
         metadata
             .metadata
             .global_metadata
@@ -639,10 +1963,14 @@ pub trait CSType: Sized {
                     .get(def.type_index as usize)
                     .unwrap();
 
-                Self::default_value_blob(metadata, ty.ty, def.data_index.index() as usize)
+                Self::typed_default_value(metadata, ty, cpp_name, def.data_index.index() as usize)
             })
     }
-    fn param_default_value(metadata: &Metadata, parameter_index: ParameterIndex) -> Option<String> {
+    fn param_default_value(
+        metadata: &Metadata,
+        parameter_index: ParameterIndex,
+        cpp_name: &str,
+    ) -> Option<String> {
         metadata
             .metadata
             .global_metadata
@@ -681,7 +2009,7 @@ pub trait CSType: Sized {
                     }
                 }
 
-                Self::default_value_blob(metadata, ty.ty, def.data_index.index() as usize)
+                Self::typed_default_value(metadata, ty, cpp_name, def.data_index.index() as usize)
             })
     }
 
@@ -696,7 +2024,9 @@ pub trait CSType: Sized {
 
         let _context_tag = ctx_collection.get_context_root_tag(tag);
         let cpp_type = self.get_mut_cpp_type();
-        let mut nested_types: HashMap<TypeData, String> = cpp_type
+        // Type the name is used in, for warnings
+        let owner = cpp_type.formatted_complete_cpp_name().clone();
+        let mut nested_types: FxHashMap<TypeData, String> = cpp_type
             .nested_types_flattened()
             .into_iter()
             .map(|(t, c)| (t, c.formatted_complete_cpp_name().clone()))
@@ -721,6 +2051,10 @@ pub trait CSType: Sized {
             _ => (),
         };
 
+        if let Some(primitive) = primitive_cpp_name(typ.ty) {
+            return primitive.to_string();
+        }
+
         match typ.ty {
             Il2CppTypeEnum::Object => {
                 requirements.need_wrapper();
@@ -752,9 +2086,13 @@ pub trait CSType: Sized {
                         .insert(CppInclude::new_context(to_incl));
                 }
                 let inc = CppInclude::new_context(to_incl);
-                let to_incl_ty = ctx_collection
-                    .get_cpp_type(typ.data)
-                    .unwrap_or_else(|| panic!("Unable to get type to include {:?}", typ.data));
+                // Empty when the type failed to generate, e.g. with --keep-going
+                let Some(to_incl_ty) = ctx_collection.get_cpp_type(typ.data) else {
+                    println!("Warning! Unable to get type to include {typ:?}, falling back to void*");
+                    UNKNOWN_TYPE_COUNT.fetch_add(1, Ordering::Relaxed);
+                    record_warning("unknown types fell back to void*", owner);
+                    return format!("/* MISSING TYPE! {:?} */ void*", typ.data);
+                };
 
                 // Forward declare it
                 if !add_include {
@@ -780,18 +2118,36 @@ pub trait CSType: Sized {
 
                 format!("::ArrayW<{generic}>")
             }
-            Il2CppTypeEnum::Mvar | Il2CppTypeEnum::Var => match typ.data {
+            Il2CppTypeEnum::Array => {
+                // TODO: brocolib's TypeData::ArrayType carries no rank/bounds yet,
+                // once it does emit a MultidimensionalArrayW<Rank, Element> here
+                println!(
+                    "Warning! Multidimensional array rank is unavailable for {typ:?}, falling back to ::bs_hook::Il2CppWrapperType"
+                );
+                record_warning(
+                    "multidimensional arrays fell back to Il2CppWrapperType",
+                    owner,
+                );
+                requirements.need_wrapper();
+                "::bs_hook::Il2CppWrapperType".to_string()
+            }
+            Il2CppTypeEnum::Mvar | Il2CppTypeEnum::Var => {
                 // TODO: Alias to actual generic
-                TypeData::GenericParameterIndex(index) => {
-                    let generic_param =
-                        &metadata.metadata.global_metadata.generic_parameters[index];
-
-                    let name = generic_param.name(metadata.metadata);
+                let name = match typ.data {
+                    TypeData::GenericParameterIndex(index) => Some(
+                        metadata.metadata.global_metadata.generic_parameters[index]
+                            .name(metadata.metadata),
+                    ),
+                    _ => {
+                        println!("Warning! Generic parameter without an index {typ:?}, falling back to void*");
+                        UNKNOWN_TYPE_COUNT.fetch_add(1, Ordering::Relaxed);
+                        record_warning("unknown types fell back to void*", owner);
+                        None
+                    }
+                };
 
-                    name.to_string()
-                }
-                _ => todo!(),
-            },
+                generic_parameter_cpp_name(name, typ)
+            }
             Il2CppTypeEnum::Genericinst => match typ.data {
                 TypeData::GenericClassIndex(e) => {
                     let mr = &metadata.metadata_registration;
@@ -818,38 +2174,24 @@ pub trait CSType: Sized {
 
                 _ => panic!("Unknown type data for generic inst {typ:?}!"),
             },
-            Il2CppTypeEnum::I1 => "int8_t".to_string(),
-            Il2CppTypeEnum::I2 => "int16_t".to_string(),
-            Il2CppTypeEnum::I4 => "int32_t".to_string(),
-            // TODO: We assume 64 bit
-            Il2CppTypeEnum::I | Il2CppTypeEnum::I8 => "int64_t".to_string(),
-            Il2CppTypeEnum::U1 => "uint8_t".to_string(),
-            Il2CppTypeEnum::U2 => "uint16_t".to_string(),
-            Il2CppTypeEnum::U4 => "uint32_t".to_string(),
-            // TODO: We assume 64 bit
-            Il2CppTypeEnum::U | Il2CppTypeEnum::U8 => "uint64_t".to_string(),
-
-            // https://learn.microsoft.com/en-us/nimbusml/concepts/types
-            // https://en.cppreference.com/w/cpp/types/floating-point
-            Il2CppTypeEnum::R4 => "float32_t".to_string(),
-            Il2CppTypeEnum::R8 => "float64_t".to_string(),
-
-            Il2CppTypeEnum::Void => "void".to_string(),
-            Il2CppTypeEnum::Boolean => "bool".to_string(),
-            Il2CppTypeEnum::Char => "char16_t".to_string(),
             Il2CppTypeEnum::String => {
                 requirements.needs_stringw_include();
                 "::StringW".to_string()
             }
-            Il2CppTypeEnum::Ptr => "void*".to_owned(),
-            // TODO: Void and the other primitives
-            _ => format!("/* UNKNOWN TYPE! {typ:?} */"),
+            _ => {
+                // Keep the header compiling, pointer sized like most of what ends up here
+                println!("Warning! Unhandled type {typ:?}, falling back to void*");
+                UNKNOWN_TYPE_COUNT.fetch_add(1, Ordering::Relaxed);
+                record_warning("unknown types fell back to void*", owner);
+                format!("/* UNKNOWN TYPE! {:?} */ void*", typ.ty)
+            }
         }
     }
 
-    fn classof_cpp_name(&self) -> String {
+    fn classof_cpp_name(&self, config: &GenerationConfig) -> String {
         format!(
-            "::il2cpp_utils::il2cpp_type_check::il2cpp_no_arg_class<{}>::get",
+            "{}::il2cpp_type_check::il2cpp_no_arg_class<{}>::get",
+            config.il2cpp_utils_namespace,
             self.get_cpp_type().formatted_complete_cpp_name()
         )
     }
@@ -871,3 +2213,127 @@ impl CSType for CppType {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn param_name_keeps_real_names() {
+        assert_eq!(param_name("value", 0, &["value"], &[]), "value");
+    }
+
+    #[test]
+    fn param_name_names_unnamed_by_index() {
+        assert_eq!(param_name("", 2, &["a", "b", ""], &["a", "b"]), "arg2");
+    }
+
+    #[test]
+    fn param_name_renames_duplicates() {
+        assert_eq!(param_name("x", 1, &["x", "x"], &["x"]), "arg1");
+    }
+
+    #[test]
+    fn param_name_skips_taken_names() {
+        // arg1 is a later parameter's real name, arg2 was already generated
+        let real_names = ["", "arg1", ""];
+        assert_eq!(param_name("", 0, &real_names, &[]), "arg0");
+        assert_eq!(param_name("", 2, &real_names, &["arg0", "arg1"]), "arg2");
+        assert_eq!(param_name("", 0, &["", "arg0"], &[]), "arg1");
+        assert_eq!(param_name("", 1, &["", "", "arg2"], &["arg1"]), "arg3");
+    }
+
+    #[test]
+    fn int64_literal_suffixes() {
+        assert_eq!(int64_literal(0), "0LL");
+        assert_eq!(int64_literal(-1), "-1LL");
+        assert_eq!(int64_literal(i64::MAX), "9223372036854775807LL");
+    }
+
+    #[test]
+    fn int64_literal_min_is_a_valid_expression() {
+        assert_eq!(int64_literal(i64::MIN), "(-9223372036854775807LL - 1)");
+    }
+
+    #[test]
+    fn uint64_literal_suffixes() {
+        assert_eq!(uint64_literal(0), "0ULL");
+        assert_eq!(uint64_literal(u64::MAX), "18446744073709551615ULL");
+    }
+
+    #[test]
+    fn enum_default_value_constructs_the_enum() {
+        assert_eq!(
+            enum_default_value("::System::DayOfWeek", "1"),
+            "::System::DayOfWeek(1)"
+        );
+        assert_eq!(
+            enum_default_value("::Flags", &int64_literal(i64::MIN)),
+            "::Flags((-9223372036854775807LL - 1))"
+        );
+        assert_eq!(
+            enum_default_value("::Flags", &uint64_literal(u64::MAX)),
+            "::Flags(18446744073709551615ULL)"
+        );
+    }
+
+    #[test]
+    fn generic_parameter_by_name() {
+        assert_eq!(
+            generic_parameter_cpp_name(Some("T"), &Il2CppTypeEnum::Var),
+            "T"
+        );
+    }
+
+    #[test]
+    fn generic_parameter_without_index_is_void_pointer() {
+        let name = generic_parameter_cpp_name(None, &Il2CppTypeEnum::Var);
+        assert_eq!(name, "/* UNKNOWN GENERIC PARAMETER! Var */ void*");
+    }
+
+    #[test]
+    fn function_pointers_are_void_pointers() {
+        assert_eq!(
+            primitive_cpp_name(Il2CppTypeEnum::Fnptr),
+            Some("/* function pointer */ void*")
+        );
+    }
+
+    #[test]
+    fn primitive_names() {
+        assert_eq!(primitive_cpp_name(Il2CppTypeEnum::Ptr), Some("void*"));
+        assert_eq!(primitive_cpp_name(Il2CppTypeEnum::I), Some("int64_t"));
+        assert_eq!(primitive_cpp_name(Il2CppTypeEnum::Char), Some("char16_t"));
+        // Need an include or a declaration
+        assert_eq!(primitive_cpp_name(Il2CppTypeEnum::String), None);
+        assert_eq!(primitive_cpp_name(Il2CppTypeEnum::Class), None);
+    }
+
+    #[test]
+    fn unconstrained_generic_parameter() {
+        assert_eq!(generic_constraint("T", 0, vec![]), None);
+    }
+
+    #[test]
+    fn generic_constraints_in_csharp_order() {
+        let flags = GENERIC_PARAMETER_ATTRIBUTE_REFERENCE_TYPE_CONSTRAINT
+            | GENERIC_PARAMETER_ATTRIBUTE_DEFAULT_CONSTRUCTOR_CONSTRAINT;
+        assert_eq!(
+            generic_constraint("T", flags, vec!["IFoo".to_string()]).as_deref(),
+            Some("T: class, IFoo, new()")
+        );
+        assert_eq!(
+            generic_constraint(
+                "TValue",
+                GENERIC_PARAMETER_ATTRIBUTE_NOT_NULLABLE_VALUE_TYPE_CONSTRAINT,
+                vec![]
+            )
+            .as_deref(),
+            Some("TValue: struct")
+        );
+        assert_eq!(
+            generic_constraint("T", 0, vec!["IFoo".to_string(), "IBar".to_string()]).as_deref(),
+            Some("T: IFoo, IBar")
+        );
+    }
+}
@@ -31,10 +31,18 @@ pub struct CppCommentedString {
     pub comment: Option<String>,
 }
 
+/// Code written as is, e.g. a using-declaration or a constexpr table
+#[derive(Debug, Eq, Hash, PartialEq, Clone)]
+pub struct CppLine {
+    pub line: String,
+}
+
 #[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct CppInclude {
     pub include: PathBuf,
     pub system: bool,
+    /// Written in angle brackets relative to this directory instead of quoted
+    pub relative_to: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -44,6 +52,7 @@ pub enum CppMember {
     MethodImpl(CppMethodImpl),
     Property(CppProperty),
     Comment(CppCommentedString),
+    CppLine(CppLine),
     ConstructorDecl(CppConstructorDecl),
     ConstructorImpl(CppConstructorImpl),
 }
@@ -68,6 +77,12 @@ pub struct CppMethodSizeStruct {
     pub interface_clazz_of: String,
     pub is_final: bool,
     pub slot: Option<u16>,
+    // Same as the method's, e.g. const
+    pub suffix_modifiers: String,
+    /// Namespace of the il2cpp_utils helpers, e.g. ::il2cpp_utils
+    pub il2cpp_utils: String,
+    /// Class static methods are looked up on
+    pub classof_call: String,
 }
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CppField {
@@ -79,6 +94,8 @@ pub struct CppField {
     pub classof_call: String,
     pub literal_value: Option<String>,
     pub use_wrapper: bool,
+    /// C# modifiers of the field, e.g. "private static readonly"
+    pub cs_modifiers: String,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -120,6 +137,8 @@ pub struct CppMethodDecl {
     // TODO: Add all descriptions missing for the method
     pub method_data: CppMethodData,
     pub is_virtual: bool,
+    /// Defined inline instead of calling into il2cpp, one statement per line
+    pub body: Option<Vec<String>>,
 }
 
 // TODO: Generic
@@ -134,6 +153,14 @@ pub struct CppMethodImpl {
     pub return_type: String,
     pub parameters: Vec<CppParam>,
     pub instance: bool,
+    /// Whether a CppMethodSizeStruct provides the MethodInfo, otherwise it's looked up by name
+    pub has_size_struct: bool,
+    /// Class static methods are looked up on
+    pub classof_call: String,
+    /// Call straight into the method's address instead of resolving it at runtime
+    pub direct_call: Option<CppDirectCall>,
+    /// Namespace of the il2cpp_utils helpers, e.g. ::il2cpp_utils
+    pub il2cpp_utils: String,
 
     pub template: CppTemplate,
     // TODO: Use bitflags to indicate these attributes
@@ -151,6 +178,18 @@ pub struct CppMethodImpl {
     pub prefix_modifiers: String,
 }
 
+/// A method called through a raw function pointer, with wrappers lowered to the il2cpp ABI
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CppDirectCall {
+    pub addrs: u64,
+    /// Instance argument as a void*
+    pub instance_arg: String,
+    /// ABI type and argument expression of each parameter, e.g. void* and obj.convert()
+    pub params: Vec<(String, String)>,
+    /// ABI return type, converted back to the method's return type when it differs
+    pub return_ty: String,
+}
+
 // TODO: Generics
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CppConstructorDecl {
@@ -164,7 +203,12 @@ pub struct CppConstructorImpl {
 
     pub parameters: Vec<CppParam>,
     pub is_constexpr: bool,
+    pub is_explicit: bool,
+    /// Base type and arguments to forward to instead of creating a new object
+    pub base_ctor: Option<(String, String)>,
     pub template: CppTemplate,
+    /// Namespace of the il2cpp_utils helpers, e.g. ::il2cpp_utils
+    pub il2cpp_utils: String,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -190,6 +234,40 @@ impl CppForwardDeclare {
     }
 }
 
+impl CppMethodDecl {
+    /// A helper defined inline, with no il2cpp method behind it
+    pub fn new_inline(
+        cpp_name: String,
+        return_type: String,
+        parameters: Vec<CppParam>,
+        instance: bool,
+        body: Vec<String>,
+    ) -> Self {
+        Self {
+            cpp_name,
+            return_type,
+            parameters,
+            instance,
+            template: CppTemplate::default(),
+            suffix_modifiers: Default::default(),
+            prefix_modifiers: Default::default(),
+            method_data: CppMethodData {
+                estimated_size: 0,
+                addrs: 0,
+            },
+            is_virtual: false,
+            body: Some(body),
+        }
+    }
+}
+
+impl CppMethodData {
+    /// Abstract methods and the like have no address
+    pub fn has_addrs(&self) -> bool {
+        self.addrs != 0 && self.addrs != u64::MAX
+    }
+}
+
 impl CppParam {
     pub fn params_as_args(params: &[CppParam]) -> String {
         params
@@ -213,26 +291,33 @@ impl CppParam {
         params.iter().map(|p| &p.ty).join(", ")
     }
 
-    pub fn params_il2cpp_types(params: &[CppParam]) -> String {
+    pub fn params_il2cpp_types(params: &[CppParam], il2cpp_utils: &str) -> String {
         params
             .iter()
-            .map(|p| format!("::il2cpp_utils::ExtractType({})", p.name))
+            .map(|p| format!("{il2cpp_utils}::ExtractType({})", p.name))
             .join(", ")
     }
 }
 
 impl CppInclude {
     pub fn new_context(context: &CppContext) -> Self {
-        Self {
-            include: context.fundamental_path.clone(),
-            system: false,
-        }
+        context.include(&context.fundamental_path)
     }
 
     pub fn new_system(str: PathBuf) -> Self {
         Self {
             include: str,
             system: true,
+            relative_to: None,
+        }
+    }
+
+    /// A header cordl generates, written relative to root when one is given
+    pub fn new_generated(str: PathBuf, root: Option<PathBuf>) -> Self {
+        Self {
+            include: str,
+            system: false,
+            relative_to: root,
         }
     }
 
@@ -240,6 +325,7 @@ impl CppInclude {
         Self {
             include: str,
             system: false,
+            relative_to: None,
         }
     }
 }
@@ -5,6 +5,14 @@ use super::{
 use itertools::Itertools;
 use std::io::Write;
 
+/// Method qualifiers such as const go after the parameter list
+fn suffix(suffix_modifiers: &str) -> String {
+    match suffix_modifiers.is_empty() {
+        true => "".to_string(),
+        false => format!(" {suffix_modifiers}"),
+    }
+}
+
 impl Writable for CppTemplate {
     fn write(&self, writer: &mut CppWriter) -> color_eyre::Result<()> {
         if !self.names.is_empty() {
@@ -59,10 +67,20 @@ impl Writable for CppCommentedString {
     }
 }
 
+impl Writable for CppLine {
+    fn write(&self, writer: &mut CppWriter) -> color_eyre::Result<()> {
+        writeln!(writer, "{}", self.line)?;
+        Ok(())
+    }
+}
+
 impl Writable for CppInclude {
     fn write(&self, writer: &mut CppWriter) -> color_eyre::Result<()> {
         if self.system {
             writeln!(writer, "#include <{}>", self.include.to_str().unwrap())?;
+        } else if let Some(root) = &self.relative_to {
+            let include = self.include.strip_prefix(root).unwrap_or(&self.include);
+            writeln!(writer, "#include <{}>", include.to_str().unwrap())?;
         } else {
             writeln!(writer, "#include \"{}\"", self.include.to_str().unwrap())?;
         }
@@ -74,8 +92,8 @@ impl Writable for CppField {
     fn write(&self, writer: &mut super::writer::CppWriter) -> color_eyre::Result<()> {
         writeln!(
             writer,
-            "// Field: name: {}, Type Name: {}, Offset: 0x{:x}",
-            self.name, self.ty, self.offset
+            "// Field: name: {}, Type Name: {}, Offset: 0x{:x}, C# Modifiers: {}",
+            self.name, self.ty, self.offset, self.cs_modifiers
         )?;
 
         let cpp_name = if self.literal_value.is_some() {
@@ -122,31 +140,49 @@ impl Writable for CppField {
 impl Writable for CppMethodDecl {
     // declaration
     fn write(&self, writer: &mut super::writer::CppWriter) -> color_eyre::Result<()> {
-        writeln!(
-            writer,
-            "// Method: name: {}, Return Type Name: {} Parameters: {:?} Addr {:x} Size {:x}",
-            self.cpp_name,
-            self.return_type,
-            self.parameters,
-            self.method_data.addrs,
-            self.method_data.estimated_size
-        )?;
+        // Inline helpers have no il2cpp method behind them
+        if self.body.is_none() {
+            writeln!(
+                writer,
+                "// Method: name: {}, Return Type Name: {} Parameters: {:?} Addr {:x} Size {:x}",
+                self.cpp_name,
+                self.return_type,
+                self.parameters,
+                self.method_data.addrs,
+                self.method_data.estimated_size
+            )?;
+        }
 
         self.template.write(writer)?;
 
+        if !self.prefix_modifiers.is_empty() {
+            write!(writer, "{} ", self.prefix_modifiers)?;
+        }
         if !self.instance {
             write!(writer, "static ")?;
         } else if self.is_virtual {
             write!(writer, "virtual ")?;
         }
-        writeln!(
+        write!(
             writer,
-            "{} {}({});",
+            "{} {}({}){}",
             self.return_type,
             self.cpp_name,
-            CppParam::params_as_args(&self.parameters)
+            CppParam::params_as_args(&self.parameters),
+            suffix(&self.suffix_modifiers)
         )?;
 
+        match &self.body {
+            Some(body) => {
+                writeln!(writer, " {{")?;
+                for line in body {
+                    writeln!(writer, "  {line}")?;
+                }
+                writeln!(writer, "}}")?;
+            }
+            None => writeln!(writer, ";")?,
+        }
+
         Ok(())
     }
 }
@@ -154,6 +190,7 @@ impl Writable for CppMethodDecl {
 impl Writable for CppMethodImpl {
     // declaration
     fn write(&self, writer: &mut super::writer::CppWriter) -> color_eyre::Result<()> {
+        let il2cpp_utils = &self.il2cpp_utils;
         self.template.write(writer)?;
 
         if !self.instance {
@@ -161,9 +198,10 @@ impl Writable for CppMethodImpl {
         }
 
         // Start
+        let suffix = suffix(&self.suffix_modifiers);
         writeln!(
             writer,
-            "{} {}::{}({}){{",
+            "{} {}::{}({}){suffix} {{",
             self.return_type,
             self.holder_cpp_name,
             self.cpp_method_name,
@@ -176,16 +214,77 @@ impl Writable for CppMethodImpl {
 
         // Body
 
+        // Call straight into the binary, no runtime lookups
+        if let Some(direct_call) = &self.direct_call {
+            let fn_params = self
+                .instance
+                .then_some("void*".to_string())
+                .into_iter()
+                .chain(direct_call.params.iter().map(|(ty, _)| ty.clone()))
+                .chain(std::iter::once("const MethodInfo*".to_string()))
+                .join(", ");
+            let fn_args = self
+                .instance
+                .then(|| direct_call.instance_arg.clone())
+                .into_iter()
+                .chain(direct_call.params.iter().map(|(_, arg)| arg.clone()))
+                .chain(std::iter::once("nullptr".to_string()))
+                .join(", ");
+
+            writeln!(
+                writer,
+                "using ___internal__fn = {} (*)({fn_params});",
+                direct_call.return_ty
+            )?;
+            writeln!(writer, "static auto ___internal__method_ptr = reinterpret_cast<___internal__fn>(getRealOffset(reinterpret_cast<void*>(0x{:x})));", direct_call.addrs)?;
+            match direct_call.return_ty == self.return_type {
+                true => writeln!(writer, "return ___internal__method_ptr({fn_args});")?,
+                // Reference types come back as a raw pointer to wrap
+                false => writeln!(
+                    writer,
+                    "return {}(___internal__method_ptr({fn_args}));",
+                    self.return_type
+                )?,
+            }
+            writeln!(writer, "}}")?;
+            return Ok(());
+        }
+
         let complete_type_name = format!("{}::{}", self.holder_cpp_namespaze, self.holder_cpp_name);
         let params_format = CppParam::params_types(&self.parameters);
+        // il2cpp_utils takes the instance as mutable, this is const with --const-methods
+        let this = match self
+            .suffix_modifiers
+            .split_whitespace()
+            .any(|m| m == "const")
+        {
+            true => format!("const_cast<{complete_type_name}*>(this)"),
+            false => "this".to_string(),
+        };
 
-        writeln!(writer, "static auto ___internal_method = ::il2cpp_utils::il2cpp_type_check::MetadataGetter<static_cast<{} ({complete_type_name}::*)({params_format})>(&{complete_type_name}::{})>::methodInfo();",
-            self.return_type,
-            self.cpp_method_name)?;
+        if self.has_size_struct {
+            writeln!(writer, "static auto ___internal__method = {il2cpp_utils}::il2cpp_type_check::MetadataGetter<static_cast<{} ({complete_type_name}::*)({params_format}){suffix}>(&{complete_type_name}::{})>::methodInfo();",
+                self.return_type,
+                self.cpp_method_name)?;
+        } else {
+            // Static methods have no instance, look them up on the class instead
+            let class_target = format!("{}()", self.classof_call);
+            let lookup_target = match self.instance {
+                true => &this,
+                false => &class_target,
+            };
+            writeln!(writer, "static auto ___internal__method = THROW_UNLESS({il2cpp_utils}::FindMethod({lookup_target}, \"{}\", std::vector<Il2CppClass*>{{}}, ::std::vector<const Il2CppType*>{{{}}}));",
+                self.cs_method_name,
+                CppParam::params_il2cpp_types(&self.parameters, il2cpp_utils))?;
+        }
 
+        let instance_arg = match self.instance {
+            true => this,
+            false => "static_cast<Il2CppObject*>(nullptr)".to_string(),
+        };
         write!(
             writer,
-            "return ::il2cpp_utils::RunMethodRethrow<{}, false>(this, ___internal__method,",
+            "return {il2cpp_utils}::RunMethodRethrow<{}, false>({instance_arg}, ___internal__method",
             self.return_type
         )?;
 
@@ -221,11 +320,16 @@ impl Writable for CppConstructorDecl {
 impl Writable for CppConstructorImpl {
     // declaration
     fn write(&self, writer: &mut super::writer::CppWriter) -> color_eyre::Result<()> {
+        let il2cpp_utils = &self.il2cpp_utils;
         writeln!(writer, "// Ctor Parameters {:?}", self.parameters)?;
 
         // Constructor
         self.template.write(writer)?;
 
+        if self.is_explicit {
+            write!(writer, "explicit ")?;
+        }
+
         if self.is_constexpr {
             // TODO:
             write!(
@@ -254,11 +358,14 @@ impl Writable for CppConstructorImpl {
                     .collect_vec()
                     .join(",")
             )?;
+        } else if let Some((base, args)) = &self.base_ctor {
+            // Forward to base constructor
+            writeln!(writer, " : {base}({args}) {{")?;
         } else {
             // Call base constructor
             writeln!(
             writer,
-            " : ::bs_hook::Il2CppWrapperType(::il2cpp_utils::New<Il2CppObject*>(classof({}), {})) {{",
+            " : ::bs_hook::Il2CppWrapperType({il2cpp_utils}::New<Il2CppObject*>(classof({}), {})) {{",
             self.holder_cpp_ty_name,
             CppParam::params_names(&self.parameters)
         )?;
@@ -317,27 +424,42 @@ impl Writable for CppProperty {
 
 impl Writable for CppMethodSizeStruct {
     fn write(&self, writer: &mut super::writer::CppWriter) -> color_eyre::Result<()> {
+        let il2cpp_utils = &self.il2cpp_utils;
         writeln!(
             writer,
             "//  Writing Method size for method: {}.{}",
             self.complete_type_name, self.cpp_method_name
         )?;
         let params_format = CppParam::params_types(&self.params);
+        let suffix = suffix(&self.suffix_modifiers);
 
         let method_info_rhs = if let Some(slot) = self.slot && !self.is_final {
-            format!("THROW_UNLESS(::il2cpp_utils::ResolveVtableSlot((*reinterpret_cast<Il2CppObject**>(this))->klass, {}(), {slot}))", 
+            format!("THROW_UNLESS({il2cpp_utils}::ResolveVtableSlot((*reinterpret_cast<Il2CppObject**>(this))->klass, {}(), {slot}))", 
               self.interface_clazz_of
             )
         } else {
-            format!("THROW_UNLESS(::il2cpp_utils::FindMethod(this, \"{}\", std::vector<Il2CppClass*>{{}}, ::std::vector<const Il2CppType*>{{{params_format}}}))", 
+            let lookup_target = match self.instance {
+                true => "this".to_string(),
+                false => format!("{}()", self.classof_call),
+            };
+            format!("THROW_UNLESS({il2cpp_utils}::FindMethod({lookup_target}, \"{}\", std::vector<Il2CppClass*>{{}}, ::std::vector<const Il2CppType*>{{{params_format}}}))", 
                 self.cpp_method_name
             )
         };
 
+        // Only expose the address when the method actually has one
+        let rva_decl = match self.method_data.has_addrs() {
+            true => format!(
+                "  constexpr static const ::std::uintptr_t rva = 0x{:x};\n\n",
+                self.method_data.addrs
+            ),
+            false => "".to_string(),
+        };
+
         writeln!(
             writer,
             "template<>
-struct ::il2cpp_utils::il2cpp_type_check::MetadataGetter<static_cast<{} ({}::*)({params_format})>(&{}::{})> {{
+struct {il2cpp_utils}::il2cpp_type_check::MetadataGetter<static_cast<{} ({}::*)({params_format}){suffix}>(&{}::{})> {{
   constexpr static const usize size() {{
     return 0x{:x};
   }}
@@ -345,7 +467,7 @@ struct ::il2cpp_utils::il2cpp_type_check::MetadataGetter<static_cast<{} ({}::*)(
     return 0x{:x};
   }}
 
-  inline static const ::MethodInfo* methodInfo() {{
+{rva_decl}  inline static const ::MethodInfo* methodInfo() {{
     return {method_info_rhs};
   }}
 }};",
@@ -367,6 +489,7 @@ impl Writable for CppMember {
             CppMember::MethodDecl(m) => m.write(writer),
             CppMember::Property(p) => p.write(writer),
             CppMember::Comment(c) => c.write(writer),
+            CppMember::CppLine(l) => l.write(writer),
             CppMember::MethodImpl(i) => i.write(writer),
             CppMember::ConstructorDecl(c) => c.write(writer),
             CppMember::ConstructorImpl(ci) => ci.write(writer),
@@ -1,7 +1,6 @@
-use std::collections::HashMap;
-
 use brocolib::global_metadata::{Il2CppTypeDefinition, MethodIndex, TypeDefinitionIndex};
 use itertools::Itertools;
+use rustc_hash::FxHashMap;
 
 use super::cpp_type::CppType;
 
@@ -22,7 +21,7 @@ impl<'a> TypeDefinitionPair<'a> {
     }
 }
 
-type TypeHandlerFn = Box<dyn Fn(&mut CppType)>;
+pub type TypeHandlerFn = Box<dyn Fn(&mut CppType)>;
 
 pub struct Metadata<'a> {
     pub metadata: &'a brocolib::Metadata<'a, 'a>,
@@ -30,12 +29,16 @@ pub struct Metadata<'a> {
     pub code_registration: &'a brocolib::runtime_metadata::Il2CppCodeRegistration<'a>,
 
     // Method index in metadata
-    pub method_calculations: HashMap<MethodIndex, MethodCalculations>,
-    pub parent_to_child_map: HashMap<TypeDefinitionIndex, Vec<TypeDefinitionPair<'a>>>,
-    pub child_to_parent_map: HashMap<TypeDefinitionIndex, TypeDefinitionPair<'a>>,
+    pub method_calculations: FxHashMap<MethodIndex, MethodCalculations>,
+    pub parent_to_child_map: FxHashMap<TypeDefinitionIndex, Vec<TypeDefinitionPair<'a>>>,
+    pub child_to_parent_map: FxHashMap<TypeDefinitionIndex, TypeDefinitionPair<'a>>,
+    // Owning image name, e.g. Assembly-CSharp.dll
+    pub tdi_to_image_name: FxHashMap<TypeDefinitionIndex, &'a str>,
 
     //
-    pub custom_type_handler: HashMap<TypeDefinitionIndex, TypeHandlerFn>
+    pub custom_type_handler: FxHashMap<TypeDefinitionIndex, TypeHandlerFn>,
+    /// Run on every type once all types are filled, in order of registration
+    pub global_type_handlers: Vec<TypeHandlerFn>,
 }
 
 impl<'a> Metadata<'a> {
@@ -91,6 +94,18 @@ impl<'a> Metadata<'a> {
             .map(|(p, c)| (p.tdi, c.into_iter().collect_vec()))
             .collect();
 
+        // type -> image
+        self.tdi_to_image_name = gm
+            .images
+            .as_vec()
+            .iter()
+            .flat_map(|img| {
+                let name = img.name(self.metadata);
+                (0..img.type_count)
+                    .map(move |i| (TypeDefinitionIndex::new(img.type_start.index() + i), name))
+            })
+            .collect();
+
         // self.parentToChildMap = childToParent
         //     .into_iter()
         //     .map(|(p, p_tdi, c)| (p_tdi, c))
@@ -107,7 +122,7 @@ impl<'a> Metadata<'a> {
             .collect();
         method_addresses_sorted.sort();
         // address -> method index in sorted list
-        let method_addresses_sorted_map: HashMap<u64, usize> = method_addresses_sorted
+        let method_addresses_sorted_map: FxHashMap<u64, usize> = method_addresses_sorted
             .iter()
             .enumerate()
             .map(|(index, m_ptr)| (*m_ptr, index))
@@ -126,8 +141,8 @@ impl<'a> Metadata<'a> {
                     .iter()
                     .find(|i| cgm.name == i.name(self.metadata))
                     .unwrap();
-                let mut method_calculations: HashMap<MethodIndex, MethodCalculations> =
-                    HashMap::new();
+                let mut method_calculations: FxHashMap<MethodIndex, MethodCalculations> =
+                    FxHashMap::default();
                 for ty in img.types(self.metadata) {
                     for (i, method) in ty.methods(self.metadata).iter().enumerate() {
                         let method_index = MethodIndex::new(ty.method_start.index() + i as u32);
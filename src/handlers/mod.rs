@@ -1 +1,2 @@
-pub mod unity;
\ No newline at end of file
+pub mod system;
+pub mod unity;
@@ -0,0 +1,192 @@
+use brocolib::runtime_metadata::TypeData;
+use color_eyre::Result;
+
+use crate::generate::{
+    constants::POINTER_SIZE,
+    context::CppContextCollection,
+    cpp_type::CppType,
+    members::{CppCommentedString, CppMember, CppMethodDecl, CppParam},
+    metadata::Metadata,
+};
+
+pub fn register_system(
+    cpp_context_collection: &CppContextCollection,
+    metadata: &mut Metadata,
+) -> Result<()> {
+    println!("Registering system handler!");
+    register_system_string_type_handler(cpp_context_collection, metadata)?;
+    register_system_span_type_handler(cpp_context_collection, metadata, "Span_1", false)?;
+    register_system_span_type_handler(cpp_context_collection, metadata, "ReadOnlySpan_1", true)?;
+
+    Ok(())
+}
+
+fn register_system_string_type_handler(
+    cpp_context_collection: &CppContextCollection,
+    metadata: &mut Metadata,
+) -> Result<()> {
+    println!("Registering System.String handler!");
+
+    let (tag, _string_cpp_context) = cpp_context_collection
+        .get()
+        .iter()
+        .find(|(_, c)| {
+            c.get_types()
+                .iter()
+                .any(|(_, t)| t.name == "String" && t.namespace == "System")
+        })
+        .unwrap_or_else(|| panic!("No System.String type found!"));
+
+    if let TypeData::TypeDefinitionIndex(tdi) = tag {
+        metadata
+            .custom_type_handler
+            .insert(*tdi, Box::new(system_string_handler));
+    }
+
+    Ok(())
+}
+
+fn register_system_span_type_handler(
+    cpp_context_collection: &CppContextCollection,
+    metadata: &mut Metadata,
+    name: &str,
+    read_only: bool,
+) -> Result<()> {
+    println!("Registering System.{name} handler!");
+
+    // Older corlibs don't have spans
+    let Some((tag, _span_cpp_context)) = cpp_context_collection.get().iter().find(|(_, c)| {
+        c.get_types()
+            .iter()
+            .any(|(_, t)| t.name == name && t.namespace == "System")
+    }) else {
+        println!("No System.{name} type found, skipping");
+        return Ok(());
+    };
+
+    if let TypeData::TypeDefinitionIndex(tdi) = tag {
+        metadata.custom_type_handler.insert(
+            *tdi,
+            Box::new(move |cpp_type| system_span_handler(cpp_type, read_only)),
+        );
+    }
+
+    Ok(())
+}
+
+fn system_span_handler(cpp_type: &mut CppType, read_only: bool) {
+    println!(
+        "Found System.{} type, adding view accessors!",
+        cpp_type.name
+    );
+
+    let Some(elem) = cpp_type.generic_args.names.first().cloned() else {
+        println!("Span has no generic argument, not adding view accessors");
+        return;
+    };
+    let constness = if read_only { " const" } else { "" };
+
+    // Spans are generic definitions, so the metadata has no offsets for their fields.
+    // Lay them out in declaration order instead, the same way the C++ struct is
+    let instance_fields = cpp_type
+        .declarations
+        .iter()
+        .filter_map(|d| match d {
+            CppMember::Field(f) if f.instance => Some(f.name.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let mut offset = 0;
+    let mut pointer_offset = None;
+    let mut length_offset = None;
+    for name in instance_fields {
+        let (size, field_offset) = match name.as_str() {
+            // _pointer in older corlibs, _reference in newer ones
+            "_pointer" | "_reference" => (POINTER_SIZE, &mut pointer_offset),
+            "_length" => (4, &mut length_offset),
+            _ => {
+                println!(
+                    "{} has an unknown field {name}, not adding view accessors",
+                    cpp_type.name
+                );
+                return;
+            }
+        };
+        // naturally aligned
+        offset = (offset + size - 1) / size * size;
+        *field_offset = Some(offset);
+        offset += size;
+    }
+    let (Some(pointer_offset), Some(length_offset)) = (pointer_offset, length_offset) else {
+        println!(
+            "{} has no pointer or length field, not adding view accessors",
+            cpp_type.name
+        );
+        return;
+    };
+
+    cpp_type.requirements.needs_int_include();
+    cpp_type
+        .declarations
+        .push(CppMember::Comment(CppCommentedString {
+            data: "".to_string(),
+            comment: Some("View over the span's pointer and length".to_string()),
+        }));
+
+    let mut data = CppMethodDecl::new_inline(
+        "data".to_string(),
+        format!("{elem}{constness}*"),
+        vec![],
+        true,
+        vec![format!(
+            "return *reinterpret_cast<{elem}{constness}* const*>(reinterpret_cast<const ::std::uint8_t*>(this) + 0x{pointer_offset:x});"
+        )],
+    );
+    let mut size = CppMethodDecl::new_inline(
+        "size".to_string(),
+        "::std::int32_t".to_string(),
+        vec![],
+        true,
+        vec![format!(
+            "return *reinterpret_cast<const ::std::int32_t*>(reinterpret_cast<const ::std::uint8_t*>(this) + 0x{length_offset:x});"
+        )],
+    );
+    let mut index = CppMethodDecl::new_inline(
+        "operator[]".to_string(),
+        format!("{elem}{constness}&"),
+        vec![CppParam {
+            name: "index".to_string(),
+            ty: "::std::int32_t".to_string(),
+            modifiers: "".to_string(),
+            def_value: None,
+        }],
+        true,
+        vec!["return data()[index];".to_string()],
+    );
+    for accessor in [&mut data, &mut size, &mut index] {
+        accessor.suffix_modifiers = "const".to_string();
+    }
+    cpp_type
+        .declarations
+        .extend([data, size, index].into_iter().map(CppMember::MethodDecl));
+}
+
+fn system_string_handler(cpp_type: &mut CppType) {
+    println!("Found System.String type, deferring to StringW!");
+    // string is always referenced as ::StringW, so don't generate a competing definition
+    cpp_type.prefix_comments.push(
+        "System.String is represented by ::StringW, its members are not generated here".to_owned(),
+    );
+
+    cpp_type.declarations.clear();
+    cpp_type.implementations.clear();
+    cpp_type.nonmember_declarations.clear();
+    cpp_type.global_declarations.clear();
+    cpp_type.nonmember_implementations.clear();
+    cpp_type.inherit.clear();
+    cpp_type.requirements.forward_declares.clear();
+
+    // StringW is all that's left to include
+    cpp_type.requirements.required_includes.clear();
+    cpp_type.requirements.needs_stringw_include();
+}
@@ -4,27 +4,172 @@
 #![feature(slice_as_chunks)]
 
 use brocolib::{global_metadata::TypeDefinitionIndex, runtime_metadata::TypeData};
-use generate::{config::GenerationConfig, context::CppContextCollection, metadata::Metadata};
+use generate::{
+    config::{DefaultNameMangler, GenerationConfig},
+    context::CppContextCollection,
+    cs_type::{record_warning, warning_summary, FAILED_TYPES_WARNING, UNKNOWN_TYPE_COUNT},
+    metadata::Metadata,
+};
 
-use std::{fs, path::PathBuf, time};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Read},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::atomic::Ordering,
+    time,
+};
 
 use clap::{Parser, Subcommand};
+use itertools::Itertools;
 
-use crate::{generate::members::CppMember, handlers::unity};
+use crate::{
+    generate::{cpp_type::CppType, cs_type::CSType, members::CppMember},
+    handlers::{system, unity},
+};
 mod generate;
 mod handlers;
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 struct Cli {
-    /// The global-metadata.dat file to use
+    /// The global-metadata.dat file to use, or - for stdin
     #[clap(short, long, value_parser, value_name = "FILE")]
     metadata: PathBuf,
 
-    /// The libil2cpp.so file to use
+    /// The libil2cpp.so file to use, or - for stdin
     #[clap(short, long, value_parser, value_name = "FILE")]
     libil2cpp: PathBuf,
 
+    /// The extension to give generated headers
+    #[clap(long, value_parser, default_value = "hpp")]
+    header_extension: String,
+
+    /// Prefix header names with their namespace instead of nesting directories
+    #[clap(long)]
+    flat_paths: bool,
+
+    /// Generate a static klass() accessor for each type's Il2CppClass*
+    #[clap(long)]
+    klass_accessor: bool,
+
+    /// Generate a static try_cast on reference types that checks the runtime class
+    #[clap(long)]
+    casts: bool,
+
+    /// Generate <, <=, > and >= for IComparable types, implemented with CompareTo
+    #[clap(long)]
+    comparisons: bool,
+
+    /// Declare instance methods const so they can be called on const references
+    #[clap(long)]
+    const_methods: bool,
+
+    /// Call methods with known addresses directly, without runtime method resolution
+    #[clap(long)]
+    direct_calls: bool,
+
+    /// Inject an include into a type, e.g. `UnityEngine.Vector3=my-mod/vector-helpers.hpp`
+    #[clap(long, value_parser = parse_extra_include, value_name = "TYPE=PATH")]
+    extra_include: Vec<(String, PathBuf)>,
+
+    /// JSON file of {"Namespace.Type": {"field": offset}} overriding metadata field offsets
+    #[clap(long, value_parser, value_name = "FILE")]
+    offsets_override: Option<PathBuf>,
+
+    /// Write a header listing every type's expected instance size
+    #[clap(long, value_parser, value_name = "FILE")]
+    size_table: Option<PathBuf>,
+
+    /// Write counts of generated types and members, plus phase timings, to a JSON file
+    #[clap(long, value_parser, value_name = "FILE")]
+    stats_json: Option<PathBuf>,
+
+    /// Generate a constexpr table of instance field names and offsets per type
+    #[clap(long)]
+    field_tables: bool,
+
+    /// Generate a constexpr <field>_offset constant for every instance field
+    #[clap(long)]
+    field_offsets: bool,
+
+    /// Generate a constexpr table of virtual method names and vtable slots per type
+    #[clap(long)]
+    slot_tables: bool,
+
+    /// Check that every include and forward declare resolves to a generated type
+    #[clap(long)]
+    validate: bool,
+
+    /// Log and skip types that panic while generating instead of aborting
+    #[clap(long)]
+    keep_going: bool,
+
+    /// Comma separated namespaces to skip generating, types in them can still be forward declared
+    #[clap(long, value_parser, value_delimiter = ',')]
+    exclude_namespaces: Vec<String>,
+
+    /// Only generate instance layouts: fields and nested types, without methods or properties
+    #[clap(long)]
+    layout_only: bool,
+
+    /// Group generated headers into a directory per assembly
+    #[clap(long)]
+    group_by_assembly: bool,
+
+    /// Generate cordl::is_convertible specializations from each type's parents and interfaces
+    #[clap(long)]
+    convertible_traits: bool,
+
+    /// Skip compiler generated fields and methods, e.g. property backing fields and lambdas
+    #[clap(long)]
+    hide_compiler_generated: bool,
+
+    /// Emit a per type hash of fields and method signatures to detect ABI changes between versions
+    #[clap(long)]
+    abi_hash: bool,
+
+    /// Generate a source file that maps C# full names to their classes for runtime lookups
+    #[clap(long)]
+    type_registry: bool,
+
+    /// Write cordl_info with the cordl version and metadata hash the headers were made from
+    #[clap(long)]
+    info_header: bool,
+
+    /// Add the generation time to cordl_info, which makes the output differ between runs
+    #[clap(long)]
+    info_timestamp: bool,
+
+    /// Don't generate property wrappers, properties are used through their get_/set_ methods
+    #[clap(long)]
+    no_property_wrappers: bool,
+
+    /// Don't generate method size structs, methods are still declared and implemented
+    #[clap(long)]
+    no_size_structs: bool,
+
+    /// Namespace of the il2cpp_utils helpers used in generated method bodies
+    #[clap(long, value_parser, default_value = "::il2cpp_utils")]
+    il2cpp_utils_namespace: String,
+
+    /// Namespace of the il2cpp_functions runtime exports used in generated helpers
+    #[clap(long, value_parser, default_value = "::il2cpp_functions")]
+    il2cpp_functions_namespace: String,
+
+    /// Collect each header's forward declares into a single _fwd header
+    #[clap(long)]
+    forward_declare_headers: bool,
+
+    /// Include generated headers as <path> relative to the output include directory
+    #[clap(long)]
+    angle_includes: bool,
+
+    /// File of old=new C# full type names to generate compatibility aliases for
+    #[clap(long)]
+    rename_map: Option<PathBuf>,
+
     #[clap(subcommand)]
     command: Option<Commands>,
 }
@@ -32,6 +177,40 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {}
 
+fn read_input(path: &Path) -> io::Result<Vec<u8>> {
+    if path != Path::new("-") {
+        return fs::read(path);
+    }
+
+    let mut data = Vec::new();
+    io::stdin().read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn parse_extra_include(s: &str) -> Result<(String, PathBuf), String> {
+    let (ty, path) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected TYPE=PATH, got `{s}`"))?;
+
+    Ok((ty.to_string(), PathBuf::from(path)))
+}
+
+/// Runs one type's generation step, catching a panic when keep_going is set.
+/// CppContextCollection restores its own bookkeeping before the panic gets here
+fn guard_type(keep_going: bool, metadata: &Metadata, tdi: TypeDefinitionIndex, f: impl FnOnce()) {
+    if !keep_going {
+        f();
+        return;
+    }
+
+    if panic::catch_unwind(AssertUnwindSafe(f)).is_err() {
+        let name = metadata.metadata.global_metadata.type_definitions[tdi]
+            .full_name(metadata.metadata, false);
+        println!("Failed to generate {name}, it may be incomplete. Continuing");
+        record_warning(FAILED_TYPES_WARNING, name);
+    }
+}
+
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
     let cli = Cli::parse();
@@ -41,13 +220,53 @@ fn main() -> color_eyre::Result<()> {
     //     command: None,
     // };
 
-    let global_metadata_data = fs::read(cli.metadata)?;
-    let elf_data = fs::read(cli.libil2cpp)?;
+    if cli.metadata == Path::new("-") && cli.libil2cpp == Path::new("-") {
+        color_eyre::eyre::bail!("Only one of --metadata and --libil2cpp can be read from stdin");
+    }
+
+    let global_metadata_data = read_input(&cli.metadata)?;
+    // FNV-1a, identifies the metadata in cordl_info.hpp
+    let metadata_hash = global_metadata_data
+        .iter()
+        .fold(0xcbf29ce484222325u64, |hash, &b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+    let elf_data = read_input(&cli.libil2cpp)?;
     let il2cpp_metadata = brocolib::Metadata::parse(&global_metadata_data, &elf_data)?;
 
+    let offset_overrides = match &cli.offsets_override {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+        None => HashMap::new(),
+    };
+
     let config = GenerationConfig {
         header_path: PathBuf::from("./codegen/include"),
         source_path: PathBuf::from("./codegen/src"),
+        header_extension: cli.header_extension,
+        flat_namespace_paths: cli.flat_paths,
+        klass_accessor: cli.klass_accessor,
+        casts: cli.casts,
+        comparisons: cli.comparisons,
+        const_methods: cli.const_methods,
+        direct_calls: cli.direct_calls,
+        extra_includes: cli.extra_include.into_iter().into_group_map(),
+        offset_overrides,
+        field_tables: cli.field_tables,
+        field_offsets: cli.field_offsets,
+        slot_tables: cli.slot_tables,
+        exclude_namespaces: cli.exclude_namespaces,
+        layout_only: cli.layout_only,
+        group_by_assembly: cli.group_by_assembly,
+        convertible_traits: cli.convertible_traits,
+        hide_compiler_generated: cli.hide_compiler_generated,
+        abi_hash: cli.abi_hash,
+        no_property_wrappers: cli.no_property_wrappers,
+        no_size_structs: cli.no_size_structs,
+        il2cpp_utils_namespace: cli.il2cpp_utils_namespace,
+        il2cpp_functions_namespace: cli.il2cpp_functions_namespace,
+        forward_declare_headers: cli.forward_declare_headers,
+        angle_includes: cli.angle_includes,
+        name_mangler: Box::new(DefaultNameMangler),
     };
 
     let mut metadata = Metadata {
@@ -57,16 +276,20 @@ fn main() -> color_eyre::Result<()> {
         method_calculations: Default::default(),
         parent_to_child_map: Default::default(),
         child_to_parent_map: Default::default(),
+        tdi_to_image_name: Default::default(),
         custom_type_handler: Default::default(),
+        global_type_handlers: Default::default(),
     };
     let t = time::Instant::now();
     println!("Parsing metadata methods");
     metadata.parse();
     println!("Finished in {}ms", t.elapsed().as_millis());
+    let mut timings = vec![("parse", t.elapsed().as_millis())];
     let mut cpp_context_collection = CppContextCollection::new();
 
     // First, make all the contexts
     println!("Making types");
+    let t = time::Instant::now();
     for tdi_u64 in 0..metadata
         .metadata
         .global_metadata
@@ -79,15 +302,26 @@ fn main() -> color_eyre::Result<()> {
         if metadata.child_to_parent_map.contains_key(&tdi) {
             continue;
         }
-        cpp_context_collection.make_from(&metadata, &config, TypeData::TypeDefinitionIndex(tdi));
+        guard_type(cli.keep_going, &metadata, tdi, || {
+            cpp_context_collection.make_from(
+                &metadata,
+                &config,
+                TypeData::TypeDefinitionIndex(tdi),
+            );
+        });
     }
 
+    timings.push(("make", t.elapsed().as_millis()));
+
     println!("Registering handlers!");
+    system::register_system(&cpp_context_collection, &mut metadata)?;
     unity::register_unity(&cpp_context_collection, &mut metadata)?;
     println!("Handlers registered!");
 
     // Fill them now
     println!("Filling root types");
+    let t = time::Instant::now();
+    let mut excluded_types = 0;
     for tdi_u64 in 0..metadata
         .metadata
         .global_metadata
@@ -100,25 +334,95 @@ fn main() -> color_eyre::Result<()> {
         if metadata.child_to_parent_map.contains_key(&tdi) {
             continue;
         }
-        cpp_context_collection.fill(&metadata, &config, TypeData::TypeDefinitionIndex(tdi));
+        // Excluded types keep their context so they can still be referenced
+        let ns =
+            metadata.metadata.global_metadata.type_definitions[tdi].namespace(metadata.metadata);
+        if config.is_namespace_excluded(ns) {
+            excluded_types += 1;
+            continue;
+        }
+        guard_type(cli.keep_going, &metadata, tdi, || {
+            cpp_context_collection.fill(&metadata, &config, TypeData::TypeDefinitionIndex(tdi));
+        });
     }
     // Fill children
     println!("Nested types pass");
-    for parent in metadata.parent_to_child_map.keys() {
-        let owner = cpp_context_collection
-            .get_cpp_type(TypeData::TypeDefinitionIndex(*parent))
-            .unwrap();
+    for &parent in metadata.parent_to_child_map.keys() {
+        guard_type(cli.keep_going, &metadata, parent, || {
+            // Missing when the owner failed to generate, which was already reported
+            let Some(owner) =
+                cpp_context_collection.get_cpp_type(TypeData::TypeDefinitionIndex(parent))
+            else {
+                return;
+            };
+
+            // **Ignore this, we no longer recurse:**
+            // skip children of children
+            // only fill first grade children of types
+            // if owner.nested {
+            //     continue;
+            // }
+
+            let owner_ty = owner.self_tag;
+
+            // Nested types have no namespace of their own, check the root type
+            let root_tdi =
+                CppType::get_tag_tdi(cpp_context_collection.get_context_root_tag(owner_ty));
+            let ns = metadata.metadata.global_metadata.type_definitions[root_tdi]
+                .namespace(metadata.metadata);
+            if config.is_namespace_excluded(ns) {
+                return;
+            }
+
+            cpp_context_collection.fill_nested_types(&metadata, &config, owner_ty);
+        });
+    }
+
+    timings.push(("fill", t.elapsed().as_millis()));
+
+    if !metadata.global_type_handlers.is_empty() {
+        println!("Running global type handlers");
+        cpp_context_collection.apply_global_handlers(&metadata.global_type_handlers);
+    }
+
+    let unknown_types = UNKNOWN_TYPE_COUNT.load(Ordering::Relaxed);
+    if unknown_types > 0 {
+        println!("Warning! {unknown_types} unhandled types were replaced with void*");
+    }
 
-        // **Ignore this, we no longer recurse:**
-        // skip children of children
-        // only fill first grade children of types
-        // if owner.nested {
-        //     continue;
-        // }
+    if cli.validate {
+        println!("Validating references");
+        let unresolved = cpp_context_collection.validate(&config);
+        println!("Found {unresolved} unresolved references");
+    }
 
-        let owner_ty = owner.self_tag;
+    if config.convertible_traits {
+        CppContextCollection::write_convertible_traits(&config.convertible_traits_path())?;
+    }
+
+    if let Some(rename_map) = &cli.rename_map {
+        let renames = fs::read_to_string(rename_map)?;
+        cpp_context_collection.write_rename_shims(&metadata, &config, &renames)?;
+    }
 
-        cpp_context_collection.fill_nested_types(&metadata, &config, owner_ty);
+    if cli.type_registry {
+        cpp_context_collection.write_type_registry(&metadata, &config)?;
+    }
+
+    if let Some(size_table) = &cli.size_table {
+        cpp_context_collection.write_size_table(size_table)?;
+    }
+
+    if let Some(stats_json) = &cli.stats_json {
+        cpp_context_collection.write_stats_json(&metadata, stats_json, excluded_types, &timings)?;
+    }
+
+    if cli.info_header {
+        CppContextCollection::write_info(
+            &config.support_header_path("cordl_info"),
+            metadata_hash,
+            cli.info_timestamp,
+        )?;
     }
 
     // for t in &metadata.type_definitions {
@@ -240,5 +544,13 @@ fn main() -> color_eyre::Result<()> {
     //     context.write().unwrap();
     // }
 
+    let warnings = warning_summary();
+    if !warnings.is_empty() {
+        println!("Warning summary:");
+        for warning in warnings {
+            println!("  {warning}");
+        }
+    }
+
     Ok(())
 }